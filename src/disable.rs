@@ -0,0 +1,180 @@
+//! No-op backend of `disable` feature
+//!
+//! Replaces all bindings, so that no JS function is imported.
+
+use wasm_bindgen::JsValue;
+
+#[inline(always)]
+pub fn error(_: &str) {
+}
+
+#[inline(always)]
+pub fn warn(_: &str) {
+}
+
+#[inline(always)]
+pub fn info(_: &str) {
+}
+
+#[inline(always)]
+pub fn debug(_: &str) {
+}
+
+#[inline(always)]
+pub fn log(_: &str) {
+}
+
+#[inline(always)]
+pub fn trace(_: &str) {
+}
+
+#[inline(always)]
+pub fn dir(_: &str) {
+}
+
+#[inline(always)]
+pub fn group_start(_: &str) {
+}
+
+#[inline(always)]
+pub fn group_collapsed_start(_: &str) {
+}
+
+#[inline(always)]
+pub fn group_end() {
+}
+
+#[inline(always)]
+pub fn console_assert(_: bool, _: &str) {
+}
+
+#[inline(always)]
+pub fn console_count(_: &str) {
+}
+
+#[inline(always)]
+pub fn console_count_reset(_: &str) {
+}
+
+#[inline(always)]
+pub fn console_clear() {
+}
+
+#[inline(always)]
+pub fn console_time(_: &str) {
+}
+
+#[inline(always)]
+pub fn console_time_end(_: &str) {
+}
+
+#[inline(always)]
+pub fn error_styled(_: &str, _: &str) {
+}
+
+#[inline(always)]
+pub fn warn_styled(_: &str, _: &str) {
+}
+
+#[inline(always)]
+pub fn info_styled(_: &str, _: &str) {
+}
+
+#[inline(always)]
+pub fn log_styled(_: &str, _: &str) {
+}
+
+#[inline(always)]
+pub fn debug_styled(_: &str, _: &str) {
+}
+
+#[inline(always)]
+pub fn trace_styled(_: &str, _: &str) {
+}
+
+#[inline(always)]
+pub fn error_val(_: &JsValue) {
+}
+
+#[inline(always)]
+pub fn warn_val(_: &JsValue) {
+}
+
+#[inline(always)]
+pub fn info_val(_: &JsValue) {
+}
+
+#[inline(always)]
+pub fn log_val(_: &JsValue) {
+}
+
+#[inline(always)]
+pub fn dir_val(_: &JsValue) {
+}
+
+#[inline(always)]
+pub fn debug_val(_: &JsValue) {
+}
+
+#[inline(always)]
+pub fn trace_val(_: &JsValue) {
+}
+
+#[inline(always)]
+pub fn error_obj(_: &str, _: &JsValue) {
+}
+
+#[inline(always)]
+pub fn warn_obj(_: &str, _: &JsValue) {
+}
+
+#[inline(always)]
+pub fn info_obj(_: &str, _: &JsValue) {
+}
+
+#[inline(always)]
+pub fn log_obj(_: &str, _: &JsValue) {
+}
+
+#[inline(always)]
+pub fn debug_obj(_: &str, _: &JsValue) {
+}
+
+#[inline(always)]
+pub fn trace_obj(_: &str, _: &JsValue) {
+}
+
+#[inline(always)]
+pub fn log_values2(_: &JsValue, _: &JsValue) {
+}
+
+#[inline(always)]
+pub fn log_values3(_: &JsValue, _: &JsValue, _: &JsValue) {
+}
+
+#[inline(always)]
+pub fn log_values4(_: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue) {
+}
+
+#[inline(always)]
+pub fn log_values5(_: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue) {
+}
+
+#[inline(always)]
+pub fn log_values6(_: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue) {
+}
+
+#[inline(always)]
+pub fn log_values7(_: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue) {
+}
+
+#[allow(clippy::too_many_arguments)]
+#[inline(always)]
+pub fn log_values8(_: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue) {
+}
+
+#[cfg(feature = "timestamp")]
+#[inline(always)]
+pub fn performance_now() -> f64 {
+    0.0
+}
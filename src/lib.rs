@@ -3,6 +3,20 @@
 //! ## Features:
 //!
 //! - `std` - Enables `std::io::Write` implementation.
+//! - `alloc` - Uses growable heap buffer instead of fixed array, so that data is never split across multiple console calls.
+//! - `timestamp` - Enables `Console::with_timestamp` and `Console::with_rate_limit` using `performance.now()`.
+//! - `panic` - Enables `init_panic_hook` to write panics via `console.error`. Implies `std`.
+//! - `module-prefix` - Prefixes messages written via macros with caller's module path.
+//! - `node` - Writes text into Node.js `process.stdout`/`process.stderr` instead of browser's console.
+//! - `custom-namespace` - Writes text via methods of global `webLog` object instead of `console`, e.g. `globalThis.webLog = myLogger`. Styled messages and `JsValue`s are written via the same methods, while groups, timers, counters, `assert` and `clear` still use `console`. Namespace is bound at build time, so object must be defined before first write. Ignored when `node` is enabled.
+//! - `disable` - Disables all output at compile time: macros do not format their arguments, `Console` discards writes and no JS function is imported. Conditions of `assert!`/`warn_if!` are still evaluated.
+//! - `eager` - Macros write each formatted fragment via separate console call as soon as it is formatted, so output is not lost if formatting crashes with `panic = "abort"`.
+//! - `bench` - Provides in-memory `TestSink` and `Console::force_flush_count` to measure or test buffering without browser. Implies `std`.
+//! - `test-util` - Records last flushed messages, accessible via `captured` and `Console::peek_last_flush`, so tests can assert on output. Implies `std`.
+//! - `assert-panic` - Makes `assert_eq!`/`assert_ne!` panic after writing failure message.
+//! - `debug-as-log` - Writes `ConsoleType::Debug` via `console.log` instead of `console.debug`, which browsers hide by default behind verbose level. Messages can no longer be filtered as debug in devtools.
+//! - `explicit-newline` - Makes `println!`/`eprintln!` and other `*ln` macros append `\n`, so messages are separated when written into custom `Sink`. Browser's console already puts each message on new line, so it shows trailing empty line.
+//! - `colored` - Styles messages of `Console` without own style by its `ConsoleType`, e.g. red for `Error`. Palette can be changed via `set_style`. Ignored when `node` is enabled.
 //!
 //! ## Usage
 //!
@@ -15,8 +29,15 @@
 //! let _ = write!(writer, "Hellow World!");
 //! drop(writer); //or writer.flush();
 //!
+//! //Buffer capacity can be adjusted via `sized`, that replaces `new`
+//! let mut writer = Console::<256>::sized(ConsoleType::Info);
+//! let _ = write!(writer, "Hellow small World!");
+//! drop(writer);
+//!
 //! web_log::println!("Hello via macro!");
 //! web_log::eprintln!("Error via macro!");
+//! web_log::warnln!("Warning via macro!");
+//! web_log::traceln!("Trace via macro!");
 //! ```
 
 #![cfg_attr(not(test), no_std)]
@@ -24,13 +45,51 @@
 
 #[cfg(feature = "std")]
 extern crate std;
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-#[cfg(not(test))]
+#[cfg(all(not(test), not(feature = "disable")))]
 use wasm_bindgen::prelude::wasm_bindgen;
+pub use wasm_bindgen::JsValue;
+
+use core::{ptr, fmt};
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicU32, Ordering};
+
+use core::cmp;
+#[cfg(not(feature = "alloc"))]
+use core::mem;
+
+mod macros;
+#[cfg(all(not(test), not(feature = "disable"), feature = "node"))]
+mod node;
+#[cfg(all(not(test), not(feature = "disable"), feature = "node"))]
+use node::{error, warn, info, debug, log, trace, dir};
+#[cfg(all(not(test), feature = "disable"))]
+mod disable;
+#[cfg(all(not(test), feature = "disable"))]
+use disable::*;
 
-use core::{cmp, ptr, mem, fmt};
+#[cfg(all(not(test), not(feature = "disable"), not(feature = "node"), feature = "custom-namespace"))]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = webLog)]
+    fn error(s: &str);
+    #[wasm_bindgen(js_namespace = webLog)]
+    fn warn(s: &str);
+    #[wasm_bindgen(js_namespace = webLog)]
+    fn info(s: &str);
+    #[wasm_bindgen(js_namespace = webLog)]
+    fn debug(s: &str);
+    #[wasm_bindgen(js_namespace = webLog)]
+    fn log(s: &str);
+    #[wasm_bindgen(js_namespace = webLog)]
+    fn trace(s: &str);
+    #[wasm_bindgen(js_namespace = webLog)]
+    fn dir(s: &str);
+}
 
-#[cfg(not(test))]
+#[cfg(all(not(test), not(feature = "disable"), not(feature = "node"), not(feature = "custom-namespace")))]
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = console)]
@@ -41,266 +100,5050 @@ extern "C" {
     fn info(s: &str);
     #[wasm_bindgen(js_namespace = console)]
     fn debug(s: &str);
+    #[wasm_bindgen(js_namespace = console)]
+    fn log(s: &str);
+    #[wasm_bindgen(js_namespace = console)]
+    fn trace(s: &str);
+    #[wasm_bindgen(js_namespace = console)]
+    fn dir(s: &str);
+}
+
+#[cfg(all(not(test), not(feature = "disable")))]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = group)]
+    fn group_start(s: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = groupCollapsed)]
+    fn group_collapsed_start(s: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = groupEnd)]
+    fn group_end();
+    #[wasm_bindgen(js_namespace = console, js_name = assert)]
+    fn console_assert(condition: bool, s: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = count)]
+    fn console_count(label: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = countReset)]
+    fn console_count_reset(label: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = clear)]
+    fn console_clear();
+    #[wasm_bindgen(js_namespace = console, js_name = time)]
+    fn console_time(label: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = timeEnd)]
+    fn console_time_end(label: &str);
+}
+
+#[cfg(all(not(test), not(feature = "disable"), any(feature = "node", not(feature = "custom-namespace"))))]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn error_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = warn)]
+    fn warn_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = info)]
+    fn info_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn log_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = debug)]
+    fn debug_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = trace)]
+    fn trace_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn error_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = warn)]
+    fn warn_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = info)]
+    fn info_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn log_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = dir)]
+    fn dir_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = debug)]
+    fn debug_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = trace)]
+    fn trace_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn error_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = warn)]
+    fn warn_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = info)]
+    fn info_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn log_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = debug)]
+    fn debug_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = trace)]
+    fn trace_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn log_values2(a: &JsValue, b: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn log_values3(a: &JsValue, b: &JsValue, c: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn log_values4(a: &JsValue, b: &JsValue, c: &JsValue, d: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn log_values5(a: &JsValue, b: &JsValue, c: &JsValue, d: &JsValue, e: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn log_values6(a: &JsValue, b: &JsValue, c: &JsValue, d: &JsValue, e: &JsValue, f: &JsValue);
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn log_values7(a: &JsValue, b: &JsValue, c: &JsValue, d: &JsValue, e: &JsValue, f: &JsValue, g: &JsValue);
+    #[allow(clippy::too_many_arguments)]
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn log_values8(a: &JsValue, b: &JsValue, c: &JsValue, d: &JsValue, e: &JsValue, f: &JsValue, g: &JsValue, h: &JsValue);
+}
+
+#[cfg(all(not(test), not(feature = "disable"), not(feature = "node"), feature = "custom-namespace"))]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = webLog, js_name = error)]
+    fn error_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = webLog, js_name = warn)]
+    fn warn_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = webLog, js_name = info)]
+    fn info_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = webLog, js_name = log)]
+    fn log_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = webLog, js_name = debug)]
+    fn debug_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = webLog, js_name = trace)]
+    fn trace_styled(s: &str, css: &str);
+    #[wasm_bindgen(js_namespace = webLog, js_name = error)]
+    fn error_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = warn)]
+    fn warn_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = info)]
+    fn info_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = log)]
+    fn log_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = dir)]
+    fn dir_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = debug)]
+    fn debug_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = trace)]
+    fn trace_val(v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = error)]
+    fn error_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = warn)]
+    fn warn_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = info)]
+    fn info_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = log)]
+    fn log_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = debug)]
+    fn debug_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = trace)]
+    fn trace_obj(fmt: &str, v: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = log)]
+    fn log_values2(a: &JsValue, b: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = log)]
+    fn log_values3(a: &JsValue, b: &JsValue, c: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = log)]
+    fn log_values4(a: &JsValue, b: &JsValue, c: &JsValue, d: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = log)]
+    fn log_values5(a: &JsValue, b: &JsValue, c: &JsValue, d: &JsValue, e: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = log)]
+    fn log_values6(a: &JsValue, b: &JsValue, c: &JsValue, d: &JsValue, e: &JsValue, f: &JsValue);
+    #[wasm_bindgen(js_namespace = webLog, js_name = log)]
+    fn log_values7(a: &JsValue, b: &JsValue, c: &JsValue, d: &JsValue, e: &JsValue, f: &JsValue, g: &JsValue);
+    #[allow(clippy::too_many_arguments)]
+    #[wasm_bindgen(js_namespace = webLog, js_name = log)]
+    fn log_values8(a: &JsValue, b: &JsValue, c: &JsValue, d: &JsValue, e: &JsValue, f: &JsValue, g: &JsValue, h: &JsValue);
 }
 
 #[cfg(test)]
-fn error(_: &str) {
+fn error(text: &str) {
+    tests::capture("error", text);
 }
 
 #[cfg(test)]
-fn warn(_: &str) {
+fn warn(text: &str) {
+    tests::capture("warn", text);
 }
 
 #[cfg(test)]
-fn info(_: &str) {
+fn info(text: &str) {
+    tests::capture("info", text);
 }
 
 #[cfg(test)]
-fn debug(_: &str) {
+fn debug(text: &str) {
+    tests::capture("debug", text);
 }
 
-const BUFFER_CAPACITY: usize = 4096;
+#[cfg(test)]
+fn log(text: &str) {
+    tests::capture("log", text);
+}
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
-///Specifies method of writing into console.
-pub enum ConsoleType {
-    ///Uses `console.error`
-    Error,
-    ///Uses `console.warn`
-    Warn,
-    ///Uses `console.info`
-    Info,
-    ///Uses `console.debug`
-    Debug,
+#[cfg(test)]
+fn trace(text: &str) {
+    tests::capture("trace", text);
 }
 
-///Wrapper over browser's console
-///
-///On `Drop` performs `flush` or requires manual `flush` for written to be printed in the console.
-///Buffer capacity is 4096 bytes.
-///In case of overflow it dumps existing data to the console and overwrites with rest of it.
-pub struct Console {
-    typ: ConsoleType,
-    buffer: mem::MaybeUninit<[u8; BUFFER_CAPACITY]>,
-    len: usize,
+#[cfg(test)]
+fn dir(text: &str) {
+    tests::capture("dir", text);
 }
 
-impl Console {
-    ///Creates new instance
-    pub const fn new(typ: ConsoleType) -> Self {
-        Self {
-            typ,
-            buffer: mem::MaybeUninit::uninit(),
-            len: 0,
-        }
-    }
+#[cfg(test)]
+fn group_start(text: &str) {
+    tests::capture("group", text);
+}
 
-    #[inline(always)]
-    ///Returns content of written buffer.
-    pub fn buffer(&self) -> &[u8] {
-        unsafe {
-            core::slice::from_raw_parts(self.buffer.as_ptr() as *const u8, self.len)
-        }
-    }
+#[cfg(test)]
+fn group_collapsed_start(text: &str) {
+    tests::capture("groupCollapsed", text);
+}
 
-    #[inline(always)]
-    fn as_mut_ptr(&mut self) -> *mut u8 {
-        self.buffer.as_mut_ptr() as _
-    }
+#[cfg(test)]
+fn group_end() {
+    tests::capture("groupEnd", "");
+}
 
-    #[inline(always)]
-    ///Flushes internal buffer, if any data is available.
-    ///
-    ///Namely it dumps stored data in buffer via Console.
-    ///And resets buffered length to 0.
-    pub fn flush(&mut self) {
-        if self.len > 0 {
-            self.inner_flush();
-        }
+#[cfg(test)]
+fn console_assert(condition: bool, text: &str) {
+    if !condition {
+        tests::capture("assert", text);
     }
+}
 
-    fn inner_flush(&mut self) {
-        let text = unsafe {
-            core::str::from_utf8_unchecked(self.buffer())
-        };
-        match self.typ {
-            ConsoleType::Error => error(text),
-            ConsoleType::Warn => warn(text),
-            ConsoleType::Info => info(text),
-            ConsoleType::Debug => debug(text),
-        }
+#[cfg(test)]
+fn console_count(label: &str) {
+    tests::capture("count", label);
+}
 
-        self.len = 0;
-    }
+#[cfg(test)]
+fn console_count_reset(label: &str) {
+    tests::capture("countReset", label);
+}
 
-    #[inline]
-    fn copy_data<'a>(&mut self, text: &'a [u8]) -> &'a [u8] {
-        let mut write_len = cmp::min(BUFFER_CAPACITY.saturating_sub(self.len), text.len());
+#[cfg(test)]
+fn console_clear() {
+    tests::capture("clear", "");
+}
 
-        #[inline(always)]
-        fn is_char_boundary(text: &[u8], idx: usize) -> bool {
-            if idx == 0 {
-                return true;
-            }
+#[cfg(test)]
+fn console_time(label: &str) {
+    tests::capture("time", label);
+}
 
-            match text.get(idx) {
-                None => idx == text.len(),
-                Some(&byte) => (byte as i8) >= -0x40
-            }
-        }
+#[cfg(test)]
+fn console_time_end(label: &str) {
+    tests::capture("timeEnd", label);
+}
 
-        #[inline(never)]
-        #[cold]
-        fn shift_by_char_boundary(text: &[u8], mut size: usize) -> usize {
-            while !is_char_boundary(text, size) {
-                size -= 1;
-            }
-            size
-        }
+#[cfg(test)]
+fn error_styled(text: &str, _: &str) {
+    tests::capture("error", text);
+}
 
-        if !is_char_boundary(text, write_len) {
-            //0 is always char boundary so 0 - 1 is impossible
-            write_len = shift_by_char_boundary(text, write_len - 1);
-        }
+#[cfg(test)]
+fn warn_styled(text: &str, _: &str) {
+    tests::capture("warn", text);
+}
 
-        unsafe {
-            ptr::copy_nonoverlapping(text.as_ptr(), self.as_mut_ptr().add(self.len), write_len);
-        }
-        self.len += write_len;
-        &text[write_len..]
-    }
+#[cfg(test)]
+fn info_styled(text: &str, _: &str) {
+    tests::capture("info", text);
+}
 
-    ///Writes supplied text to the buffer.
-    ///
-    ///On buffer overflow, data is logged via `Console`
-    ///and buffer is filled with the rest of `data`
-    pub fn write_data(&mut self, mut data: &[u8]) {
-        loop {
-            data = self.copy_data(data);
+#[cfg(test)]
+fn log_styled(text: &str, _: &str) {
+    tests::capture("log", text);
+}
 
-            if data.is_empty() {
-                break;
-            } else {
-                self.flush();
-            }
-        }
-    }
+#[cfg(test)]
+fn debug_styled(text: &str, _: &str) {
+    tests::capture("debug", text);
 }
 
-impl fmt::Write for Console {
-    #[inline]
-    fn write_str(&mut self, text: &str) -> fmt::Result {
-        self.write_data(text.as_bytes());
+#[cfg(test)]
+fn trace_styled(text: &str, _: &str) {
+    tests::capture("trace", text);
+}
 
-        Ok(())
-    }
+#[cfg(test)]
+fn error_val(_: &JsValue) {
+    tests::capture("error", "JsValue");
 }
 
-#[cfg(feature = "std")]
-impl std::io::Write for Console {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.write_data(buf);
-        Ok(buf.len())
-    }
+#[cfg(test)]
+fn warn_val(_: &JsValue) {
+    tests::capture("warn", "JsValue");
+}
 
-    #[inline(always)]
-    fn flush(&mut self) -> std::io::Result<()> {
-        self.flush();
-        Ok(())
-    }
+#[cfg(test)]
+fn info_val(_: &JsValue) {
+    tests::capture("info", "JsValue");
 }
 
-impl Drop for Console {
-    #[inline]
-    fn drop(&mut self) {
-        self.flush();
-    }
+#[cfg(test)]
+fn log_val(_: &JsValue) {
+    tests::capture("log", "JsValue");
 }
 
-#[macro_export]
-///`println` alternative to write message with INFO priority.
-macro_rules! println {
-    () => {{
-        $crate::println!(" ");
-    }};
-    ($($arg:tt)*) => {{
-        use core::fmt::Write;
-        let mut writer = $crate::Console::new($crate::ConsoleType::Info);
-        let _ = write!(writer, $($arg)*);
-        drop(writer);
-    }}
+#[cfg(test)]
+fn dir_val(_: &JsValue) {
+    tests::capture("dir", "JsValue");
 }
 
-#[macro_export]
-///`eprintln` alternative to write message with ERROR priority.
-macro_rules! eprintln {
-    () => {{
-        $crate::println!(" ");
-    }};
-    ($($arg:tt)*) => {{
-        use core::fmt::Write;
-        let mut writer = $crate::Console::new($crate::ConsoleType::Error);
-        let _ = write!(writer, $($arg)*);
-        drop(writer);
-    }}
+#[cfg(test)]
+fn debug_val(_: &JsValue) {
+    tests::capture("debug", "JsValue");
 }
 
 #[cfg(test)]
-mod tests {
-    use super::{Console, ConsoleType};
-    const DATA: &str = "1234567891";
+fn trace_val(_: &JsValue) {
+    tests::capture("trace", "JsValue");
+}
 
-    #[test]
-    fn should_normal_write() {
-        let mut writer = Console::new(ConsoleType::Warn);
+#[cfg(test)]
+fn error_obj(fmt: &str, _: &JsValue) {
+    tests::capture("error", fmt);
+}
 
-        assert_eq!(writer.typ, ConsoleType::Warn);
+#[cfg(test)]
+fn warn_obj(fmt: &str, _: &JsValue) {
+    tests::capture("warn", fmt);
+}
 
-        let data = DATA.as_bytes();
+#[cfg(test)]
+fn info_obj(fmt: &str, _: &JsValue) {
+    tests::capture("info", fmt);
+}
 
-        writer.write_data(data);
-        assert_eq!(writer.len, data.len());
-        assert_eq!(writer.buffer(), data);
+#[cfg(test)]
+fn log_obj(fmt: &str, _: &JsValue) {
+    tests::capture("log", fmt);
+}
 
-        writer.write_data(b" ");
-        writer.write_data(data);
-        let expected = format!("{} {}", DATA, DATA);
-        assert_eq!(writer.len, expected.len());
-        assert_eq!(writer.buffer(), expected.as_bytes());
+#[cfg(test)]
+fn debug_obj(fmt: &str, _: &JsValue) {
+    tests::capture("debug", fmt);
+}
+
+#[cfg(test)]
+fn trace_obj(fmt: &str, _: &JsValue) {
+    tests::capture("trace", fmt);
+}
+
+#[cfg(test)]
+fn log_values2(_: &JsValue, _: &JsValue) {
+    tests::capture("log", "2 values");
+}
+
+#[cfg(test)]
+fn log_values3(_: &JsValue, _: &JsValue, _: &JsValue) {
+    tests::capture("log", "3 values");
+}
+
+#[cfg(test)]
+fn log_values4(_: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue) {
+    tests::capture("log", "4 values");
+}
+
+#[cfg(test)]
+fn log_values5(_: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue) {
+    tests::capture("log", "5 values");
+}
+
+#[cfg(test)]
+fn log_values6(_: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue) {
+    tests::capture("log", "6 values");
+}
+
+#[cfg(test)]
+fn log_values7(_: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue) {
+    tests::capture("log", "7 values");
+}
+
+#[cfg(test)]
+#[allow(clippy::too_many_arguments)]
+fn log_values8(_: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue, _: &JsValue) {
+    tests::capture("log", "8 values");
+}
+
+#[cfg(all(not(test), not(feature = "disable"), feature = "timestamp"))]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    fn performance_now() -> f64;
+}
+
+#[cfg(all(test, feature = "timestamp"))]
+fn performance_now() -> f64 {
+    tests::NOW.with(|now| now.get())
+}
+
+const BUFFER_CAPACITY: usize = 4096;
+
+#[inline(always)]
+fn is_char_boundary(text: &[u8], idx: usize) -> bool {
+    if idx == 0 {
+        return true;
     }
 
-    #[test]
-    fn should_handle_write_overflow() {
-        let mut writer = Console::new(ConsoleType::Warn);
-        let data = DATA.as_bytes();
+    match text.get(idx) {
+        None => idx == text.len(),
+        Some(&byte) => (byte as i8) >= -0x40
+    }
+}
 
-        //BUFFER_CAPACITY / DATA.len() = 148.xxx
-        for idx in 1..=409 {
-            writer.write_data(data);
-            assert_eq!(writer.len, data.len() * idx);
-        }
+#[inline(never)]
+#[cold]
+fn shift_by_char_boundary(text: &[u8], mut size: usize) -> usize {
+    while !is_char_boundary(text, size) {
+        size -= 1;
+    }
+    size
+}
 
-        writer.write_data(data);
-        assert_eq!(writer.len, 4);
-        writer.flush();
-        assert_eq!(writer.len, 0);
+#[inline]
+//Returns length of the longest prefix of `text`, that fits `max` and ends at char boundary.
+fn utf8_truncated_len(text: &[u8], max: usize) -> usize {
+    let len = cmp::min(max, text.len());
+    if is_char_boundary(text, len) {
+        len
+    } else {
+        //0 is always char boundary so 0 - 1 is impossible
+        shift_by_char_boundary(text, len - 1)
     }
+}
 
-    #[test]
-    fn should_handle_write_overflow_outside_of_char_boundary() {
-        let mut writer = Console::new(ConsoleType::Warn);
-        let data = DATA.as_bytes();
+#[inline]
+//Writes message into sink, if any, or console.
+//
+//`css` is specified when `text` starts with `%c` directive, which is stripped for sink.
+fn output_text(typ: ConsoleType, sink: Option<&'static dyn Sink>, text: &str, css: Option<&str>) {
+    let plain = match css {
+        Some(_) => text.strip_prefix("%c").unwrap_or(text),
+        None => text,
+    };
 
-        for idx in 1..=409 {
-            writer.write_data(data);
-            assert_eq!(writer.len, data.len() * idx);
+    #[cfg(feature = "test-util")]
+    if typ.is_enabled() {
+        capture(typ, plain);
+    }
+
+    if let Some(sink) = sink.or_else(global_sink) {
+        if typ.is_enabled() {
+            sink.write(typ, plain);
         }
+    } else {
+        match css {
+            Some(css) => typ.output_styled(text, css),
+            None => typ.output(text),
+        }
+    }
+}
 
-        writer.write_data(b"1234");
-        assert_eq!(4094, writer.len);
-        let unicode = "ロリ";
-        writer.write_data(unicode.as_bytes());
-        assert_eq!(writer.len, unicode.len());
-        assert_eq!(writer.buffer(), unicode.as_bytes());
+#[inline]
+///Copies as much of `src` as fits `dst`, without splitting characters, returning number of copied bytes.
+///
+///This is the same truncation as used by fixed `Console` buffer:
+///if `src` is valid UTF-8, then copied bytes are valid UTF-8 too.
+pub fn copy_utf8_truncated(dst: &mut [u8], src: &[u8]) -> usize {
+    let len = utf8_truncated_len(src, dst.len());
+    dst[..len].copy_from_slice(&src[..len]);
+    len
+}
+
+//Buffer of `Console` and `BorrowedConsole`, so that both handle overflow the same way.
+trait Buffered {
+    fn buffered_len(&self) -> usize;
+    //Copies as much of `text` as fits buffer, returning number of consumed bytes.
+    fn copy_text(&mut self, text: &str) -> usize;
+    //Writes out full buffer to make room for the rest of text.
+    fn overflow(&mut self);
+    //Writes single character, that cannot fit empty buffer, bypassing it.
+    fn output_direct(&mut self, text: &str);
+}
+
+//Writes `text` into `writer`, writing out buffer each time it is full.
+fn write_buffered(writer: &mut impl Buffered, mut text: &str) -> usize {
+    let mut written = 0;
+    loop {
+        let is_new_message = writer.buffered_len() == 0;
+        let mut copied = writer.copy_text(text);
+
+        if copied == 0 && is_new_message && !text.is_empty() {
+            //Buffer cannot fit even single character, so it is written directly to make progress.
+            copied = text.chars().next().map_or(0, char::len_utf8);
+            writer.output_direct(&text[..copied]);
+        }
+        //Iteration that starts with empty buffer always makes progress,
+        //while any other is followed by overflow, so loop always terminates.
+        debug_assert!(copied > 0 || !is_new_message || text.is_empty());
+
+        written += copied;
+        text = &text[copied..];
+
+        if text.is_empty() {
+            break written;
+        } else {
+            writer.overflow();
+        }
+    }
+}
+
+//Writes `data` via `write`, replacing invalid UTF-8 sequences with `U+FFFD`.
+//
+//Returns number of consumed bytes, which includes replaced sequences.
+fn write_lossy(mut data: &[u8], mut write: impl FnMut(&str) -> usize) -> usize {
+    let mut consumed = 0;
+    loop {
+        match core::str::from_utf8(data) {
+            Ok(text) => {
+                consumed += write(text);
+                break consumed;
+            },
+            Err(error) => {
+                let (valid, rest) = data.split_at(error.valid_up_to());
+                consumed += write(unsafe {
+                    core::str::from_utf8_unchecked(valid)
+                });
+                write("\u{FFFD}");
+
+                match error.error_len() {
+                    Some(len) => {
+                        consumed += len;
+                        data = &rest[len..];
+                    },
+                    None => {
+                        consumed += rest.len();
+                        break consumed;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+///Specifies method of writing into console.
+///
+///Variants are declared from the most severe to the most verbose.
+///Ordering follows declaration, so more verbose type is greater: `Error < Warn < ... < Trace`.
+///Hence type is allowed by `max_level` when it is less or equal to it.
+///
+///Each variant has stable numeric value, from `Error = 0` to `Trace = 6`, available via `u8::from`.
+pub enum ConsoleType {
+    ///Uses `console.error`
+    Error = 0,
+    ///Uses `console.warn`
+    Warn = 1,
+    ///Uses `console.info`
+    Info = 2,
+    ///Uses `console.log`
+    Log = 3,
+    ///Uses `console.dir`
+    ///
+    ///Doesn't support styling.
+    Dir = 4,
+    ///Uses `console.debug`
+    ///
+    ///Uses `console.log` with `debug-as-log` feature.
+    Debug = 5,
+    ///Uses `console.trace`
+    Trace = 6,
+}
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(ConsoleType::Trace as u8);
+
+///Sets the most verbose `ConsoleType` that is allowed to be written into console.
+///
+///Messages of more verbose types are discarded on flush.
+///By default everything is allowed.
+pub fn set_max_level(typ: ConsoleType) {
+    MAX_LEVEL.store(typ as u8, Ordering::Relaxed);
+}
+
+///Returns the most verbose `ConsoleType` that is allowed to be written into console.
+pub fn max_level() -> ConsoleType {
+    ConsoleType::from_level(MAX_LEVEL.load(Ordering::Relaxed))
+}
+
+#[cfg(feature = "colored")]
+struct Palette {
+    is_locked: AtomicBool,
+    styles: UnsafeCell<[&'static str; 7]>,
+}
+
+//Access to styles is serialized via `is_locked`.
+#[cfg(feature = "colored")]
+unsafe impl Sync for Palette {}
+
+#[cfg(feature = "colored")]
+static PALETTE: Palette = Palette {
+    is_locked: AtomicBool::new(false),
+    styles: UnsafeCell::new([
+        "color: red",
+        "color: orange",
+        "color: dodgerblue",
+        "",
+        "",
+        "color: gray",
+        "color: purple",
+    ]),
+};
+
+#[cfg(feature = "colored")]
+impl Palette {
+    #[inline]
+    fn with<R, F: FnOnce(&mut [&'static str; 7]) -> R>(&self, cb: F) -> R {
+        while self.is_locked.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            core::hint::spin_loop();
+        }
+        let result = cb(unsafe {
+            &mut *self.styles.get()
+        });
+        self.is_locked.store(false, Ordering::Release);
+        result
+    }
+}
+
+#[cfg(all(not(test), feature = "colored", not(feature = "node")))]
+#[inline(always)]
+fn is_palette_applied() -> bool {
+    true
+}
+
+#[cfg(all(test, feature = "colored", not(feature = "node")))]
+//Palette is applied only by tests of styling, so that others don't depend on features.
+fn is_palette_applied() -> bool {
+    tests::PALETTE.with(|palette| palette.get())
+}
+
+#[cfg(feature = "colored")]
+///Sets CSS style, that `colored` feature applies to messages of `typ`.
+///
+///Empty `css` disables styling for `typ`.
+///Instances created via `Console::styled` keep own style.
+pub fn set_style(typ: ConsoleType, css: &'static str) {
+    PALETTE.with(|styles| styles[typ as usize] = css);
+}
+
+#[cfg(feature = "colored")]
+///Returns CSS style, that `colored` feature applies to messages of `typ`.
+pub fn style(typ: ConsoleType) -> &'static str {
+    PALETTE.with(|styles| styles[typ as usize])
+}
+
+impl ConsoleType {
+    const fn from_level(level: u8) -> Self {
+        match level {
+            0 => ConsoleType::Error,
+            1 => ConsoleType::Warn,
+            2 => ConsoleType::Info,
+            3 => ConsoleType::Log,
+            4 => ConsoleType::Dir,
+            5 => ConsoleType::Debug,
+            _ => ConsoleType::Trace,
+        }
+    }
+
+    ///Returns console function, corresponding to this type.
+    ///
+    ///Note that calling it directly bypasses `max_level` filter.
+    pub fn as_fn(&self) -> fn(&str) {
+        match self {
+            ConsoleType::Error => error,
+            ConsoleType::Warn => warn,
+            ConsoleType::Info => info,
+            ConsoleType::Log => log,
+            ConsoleType::Dir => dir,
+            ConsoleType::Debug if cfg!(feature = "debug-as-log") => log,
+            ConsoleType::Debug => debug,
+            ConsoleType::Trace => trace,
+        }
+    }
+
+    fn as_value_fn(&self) -> fn(&JsValue) {
+        match self {
+            ConsoleType::Error => error_val,
+            ConsoleType::Warn => warn_val,
+            ConsoleType::Info => info_val,
+            ConsoleType::Log => log_val,
+            ConsoleType::Dir => dir_val,
+            ConsoleType::Debug if cfg!(feature = "debug-as-log") => log_val,
+            ConsoleType::Debug => debug_val,
+            ConsoleType::Trace => trace_val,
+        }
+    }
+
+    fn as_object_fn(&self) -> Option<fn(&str, &JsValue)> {
+        match self {
+            ConsoleType::Error => Some(error_obj),
+            ConsoleType::Warn => Some(warn_obj),
+            ConsoleType::Info => Some(info_obj),
+            ConsoleType::Log => Some(log_obj),
+            //console.dir doesn't support format directives
+            ConsoleType::Dir => None,
+            ConsoleType::Debug if cfg!(feature = "debug-as-log") => Some(log_obj),
+            ConsoleType::Debug => Some(debug_obj),
+            ConsoleType::Trace => Some(trace_obj),
+        }
+    }
+
+    fn as_styled_fn(&self) -> Option<fn(&str, &str)> {
+        match self {
+            ConsoleType::Error => Some(error_styled),
+            ConsoleType::Warn => Some(warn_styled),
+            ConsoleType::Info => Some(info_styled),
+            ConsoleType::Log => Some(log_styled),
+            //console.dir doesn't support format directives
+            ConsoleType::Dir => None,
+            ConsoleType::Debug if cfg!(feature = "debug-as-log") => Some(log_styled),
+            ConsoleType::Debug => Some(debug_styled),
+            ConsoleType::Trace => Some(trace_styled),
+        }
+    }
+
+    #[inline]
+    fn output_styled(&self, text: &str, css: &str) {
+        if self.is_enabled() {
+            match self.as_styled_fn() {
+                Some(styled) => styled(text, css),
+                None => (self.as_fn())(text),
+            }
+        }
+    }
+
+    #[inline]
+    fn output(&self, text: &str) {
+        if self.is_enabled() {
+            (self.as_fn())(text);
+        }
+    }
+
+    #[inline]
+    ///Returns whether messages of this type pass `max_level`.
+    ///
+    ///Always false with `disable` feature.
+    pub fn is_enabled(&self) -> bool {
+        !cfg!(all(not(test), feature = "disable")) && *self as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+    }
+
+    ///Returns lowercase name of the console method.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ConsoleType::Error => "error",
+            ConsoleType::Warn => "warn",
+            ConsoleType::Info => "info",
+            ConsoleType::Log => "log",
+            ConsoleType::Dir => "dir",
+            ConsoleType::Debug => "debug",
+            ConsoleType::Trace => "trace",
+        }
+    }
+}
+
+impl Default for ConsoleType {
+    #[inline(always)]
+    fn default() -> Self {
+        ConsoleType::Info
+    }
+}
+
+impl fmt::Display for ConsoleType {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+///Error returned when parsing or converting unknown `ConsoleType`.
+pub struct ParseConsoleTypeError;
+
+impl fmt::Display for ParseConsoleTypeError {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("unknown console type")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseConsoleTypeError {
+}
+
+impl From<ConsoleType> for &'static str {
+    #[inline(always)]
+    fn from(typ: ConsoleType) -> Self {
+        typ.as_str()
+    }
+}
+
+impl From<ConsoleType> for u8 {
+    #[inline(always)]
+    fn from(typ: ConsoleType) -> Self {
+        typ as u8
+    }
+}
+
+impl core::convert::TryFrom<u8> for ConsoleType {
+    type Error = ParseConsoleTypeError;
+
+    #[inline]
+    ///Converts numeric value of `ConsoleType`, failing if it is out of range.
+    fn try_from(value: u8) -> Result<Self, ParseConsoleTypeError> {
+        match value {
+            0..=6 => Ok(ConsoleType::from_level(value)),
+            _ => Err(ParseConsoleTypeError),
+        }
+    }
+}
+
+impl core::str::FromStr for ConsoleType {
+    type Err = ParseConsoleTypeError;
+
+    ///Parses case-insensitive name of the console method.
+    ///
+    ///Additionally accepts `warning` as `ConsoleType::Warn`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        const TYPES: [(&str, ConsoleType); 8] = [
+            ("error", ConsoleType::Error),
+            ("warn", ConsoleType::Warn),
+            ("warning", ConsoleType::Warn),
+            ("info", ConsoleType::Info),
+            ("debug", ConsoleType::Debug),
+            ("log", ConsoleType::Log),
+            ("dir", ConsoleType::Dir),
+            ("trace", ConsoleType::Trace),
+        ];
+
+        for (name, typ) in TYPES.iter() {
+            if text.eq_ignore_ascii_case(name) {
+                return Ok(*typ);
+            }
+        }
+
+        Err(ParseConsoleTypeError)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+///Error returned when message cannot fit remaining buffer capacity.
+pub struct CapacityError {
+    ///Number of bytes required to write message.
+    pub needed: usize,
+    ///Number of bytes available in buffer.
+    pub available: usize,
+}
+
+impl fmt::Display for CapacityError {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("message requires {} bytes, but only {} available", self.needed, self.available))
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityError {
+}
+
+///Destination of text messages, replacing console.
+///
+///Can be set globally via `set_sink` or per instance via `Console::with_sink`.
+///Sink receives each message, that passes `max_level`, without style directive.
+pub trait Sink: Sync {
+    ///Writes single message of type `typ`.
+    fn write(&self, typ: ConsoleType, text: &str);
+}
+
+const SINK_UNSET: u8 = 0;
+const SINK_SETTING: u8 = 1;
+const SINK_SET: u8 = 2;
+
+struct GlobalSink {
+    state: AtomicU8,
+    sink: UnsafeCell<Option<&'static dyn Sink>>,
+}
+
+//Sink is written only once, before `state` becomes `SINK_SET`, after which it is only read.
+unsafe impl Sync for GlobalSink {}
+
+static SINK: GlobalSink = GlobalSink {
+    state: AtomicU8::new(SINK_UNSET),
+    sink: UnsafeCell::new(None),
+};
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+///Error returned when global sink is already set.
+pub struct SetSinkError;
+
+impl fmt::Display for SetSinkError {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("sink is already set")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SetSinkError {
+}
+
+#[cfg(feature = "bench")]
+///In-memory `Sink`, capturing messages to measure or test buffering without browser.
+pub struct TestSink {
+    messages: std::sync::Mutex<std::vec::Vec<(ConsoleType, std::string::String)>>,
+}
+
+#[cfg(feature = "bench")]
+impl TestSink {
+    #[inline]
+    ///Creates new empty instance.
+    pub const fn new() -> Self {
+        Self {
+            messages: std::sync::Mutex::new(std::vec::Vec::new()),
+        }
+    }
+
+    #[inline]
+    ///Takes all captured messages in order of writing.
+    pub fn take(&self) -> std::vec::Vec<(ConsoleType, std::string::String)> {
+        let mut messages = self.messages.lock().unwrap_or_else(|error| error.into_inner());
+        core::mem::take(&mut *messages)
+    }
+}
+
+#[cfg(feature = "bench")]
+impl Default for TestSink {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "bench")]
+impl Sink for TestSink {
+    fn write(&self, typ: ConsoleType, text: &str) {
+        let mut messages = self.messages.lock().unwrap_or_else(|error| error.into_inner());
+        messages.push((typ, text.into()));
+    }
+}
+
+#[cfg(feature = "test-util")]
+///Number of last messages stored by `captured`.
+pub const CAPTURED_CAPACITY: usize = 64;
+
+#[cfg(feature = "test-util")]
+std::thread_local! {
+    static CAPTURED: core::cell::RefCell<std::collections::VecDeque<(ConsoleType, std::string::String)>> = const {
+        core::cell::RefCell::new(std::collections::VecDeque::new())
+    };
+}
+
+#[cfg(feature = "test-util")]
+fn capture(typ: ConsoleType, text: &str) {
+    CAPTURED.with(|captured| {
+        let mut captured = captured.borrow_mut();
+        if captured.len() == CAPTURED_CAPACITY {
+            captured.pop_front();
+        }
+        captured.push_back((typ, text.into()));
+    });
+}
+
+#[cfg(feature = "test-util")]
+///Returns last `CAPTURED_CAPACITY` messages flushed by current thread, starting from oldest.
+///
+///Messages are recorded regardless of backend, in addition to normal output.
+pub fn captured() -> std::vec::Vec<(ConsoleType, std::string::String)> {
+    CAPTURED.with(|captured| captured.borrow().iter().cloned().collect())
+}
+
+///Sets global `sink`, that receives all text messages instead of console.
+///
+///Can be set only once, which is intended for tests to capture output without browser.
+///Instances created via `Console::with_sink` still use own sink.
+pub fn set_sink(sink: &'static dyn Sink) -> Result<(), SetSinkError> {
+    match SINK.state.compare_exchange(SINK_UNSET, SINK_SETTING, Ordering::Acquire, Ordering::Relaxed) {
+        Ok(_) => {
+            unsafe {
+                *SINK.sink.get() = Some(sink);
+            }
+            SINK.state.store(SINK_SET, Ordering::Release);
+            Ok(())
+        },
+        Err(_) => Err(SetSinkError),
+    }
+}
+
+#[inline]
+fn global_sink() -> Option<&'static dyn Sink> {
+    if SINK.state.load(Ordering::Acquire) == SINK_SET {
+        unsafe {
+            *SINK.sink.get()
+        }
+    } else {
+        None
+    }
+}
+
+///Returns name of backend, that receives text messages.
+///
+///- `disabled` - `disable` feature is enabled;
+///- `sink` - global sink is set via `set_sink`;
+///- `node` - `node` feature is enabled;
+///- `webLog` - `custom-namespace` feature is enabled;
+///- `console` - browser's console.
+pub fn backend() -> &'static str {
+    if cfg!(feature = "disable") {
+        "disabled"
+    } else if global_sink().is_some() {
+        "sink"
+    } else if cfg!(feature = "node") {
+        "node"
+    } else if cfg!(feature = "custom-namespace") {
+        "webLog"
+    } else {
+        "console"
+    }
+}
+
+///Wrapper over browser's console
+///
+///On `Drop` performs `flush` or requires manual `flush` for written to be printed in the console.
+///Buffer capacity is `N` bytes, 4096 by default.
+///In case of overflow it dumps existing data to the console and overwrites with rest of it.
+///
+///With `alloc` feature buffer is stored on heap and grows on demand instead, hence `N` is ignored.
+///This way message is never split by overflow and is written only on flush, no matter how long it is,
+///at the cost of keeping whole message in memory until flush.
+///Allocated memory is retained for reuse until `Console` is dropped.
+///
+///This applies to all instances, as fixed buffer cannot grow without heap.
+///Without `alloc` feature use `new_saturating` to write each message via single console call, truncating it.
+///Note that modes such as `line_split` or `streaming` still write message via multiple console calls.
+///
+///## Thread safety
+///
+///`Console` is `Send` and `Sync`: it owns its buffer and holds no handles to JS objects.
+///Each flush calls console's functions imported by current thread,
+///so moving instance into another wasm thread (or sharing it immutably) is sound.
+///Shared writer of `with_writer` relies on `Console` being `Send`.
+pub struct Console<const N: usize = BUFFER_CAPACITY> {
+    typ: ConsoleType,
+    #[cfg(not(feature = "alloc"))]
+    buffer: mem::MaybeUninit<[u8; N]>,
+    #[cfg(feature = "alloc")]
+    buffer: alloc::vec::Vec<u8>,
+    len: usize,
+    style: &'static str,
+    prefix: &'static str,
+    trim_newline: bool,
+    line_split: bool,
+    streaming: bool,
+    normalize_newlines: bool,
+    after_cr: bool,
+    seq: bool,
+    append_newline: bool,
+    saturating: bool,
+    is_saturated: bool,
+    overflow_count: u32,
+    max_len: usize,
+    message_len: usize,
+    is_truncated: bool,
+    sink: Option<&'static dyn Sink>,
+    //Whether `colored` palette applies, when there is no own style.
+    use_palette: bool,
+    //Incomplete UTF-8 sequence at the end of last `io::Write::write`.
+    #[cfg(feature = "std")]
+    partial: [u8; 4],
+    #[cfg(feature = "std")]
+    partial_len: usize,
+    #[cfg(feature = "timestamp")]
+    timestamp: bool,
+    #[cfg(feature = "timestamp")]
+    rate_limit: RateLimit,
+}
+
+//Sequence number of next message written by `Console::with_seq`.
+static SEQ: AtomicU32 = AtomicU32::new(0);
+//Maximum length of sequence number, e.g. `#4294967295 `.
+const SEQ_PREFIX_MAX: usize = 12;
+
+#[cfg(feature = "timestamp")]
+//Duration of rate limit window in milliseconds.
+const RATE_LIMIT_WINDOW: f64 = 1000.0;
+
+#[cfg(feature = "timestamp")]
+struct RateLimit {
+    //Maximum number of messages per window, 0 if unlimited.
+    max: u32,
+    window_start: f64,
+    count: u32,
+    suppressed: u32,
+}
+
+#[cfg(feature = "timestamp")]
+///Writer that never flushes, truncating text that cannot fit.
+struct TruncatingWriter<'a, const N: usize>(&'a mut Console<N>);
+
+#[cfg(feature = "timestamp")]
+impl<const N: usize> fmt::Write for TruncatingWriter<'_, N> {
+    #[inline]
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.0.copy_data(text.as_bytes());
+        Ok(())
+    }
+}
+
+impl Console {
+    #[inline(always)]
+    ///Creates new instance
+    ///
+    ///Buffer has default capacity of 4096 bytes, use `Console::<N>::sized` to choose different one.
+    ///Constructors of other modes (e.g. `with_prefix`) are available only for default capacity.
+    pub const fn new(typ: ConsoleType) -> Self {
+        Self::sized(typ)
+    }
+
+    #[inline(always)]
+    ///Creates new instance, that starts each message with `prefix`.
+    ///
+    ///Prefix is written on the first write after creation or flush, counting against buffer capacity.
+    ///If it cannot fit buffer, it is truncated.
+    pub const fn with_prefix(typ: ConsoleType, prefix: &'static str) -> Self {
+        Self::sized(typ).prefixed(prefix)
+    }
+
+    #[inline(always)]
+    ///Creates new instance, that removes single trailing newline (`\n` or `\r\n`) on flush.
+    ///
+    ///Console already puts each message on new line, so this avoids empty lines after `writeln`.
+    pub const fn new_trimmed(typ: ConsoleType) -> Self {
+        Self::sized(typ).trimmed()
+    }
+
+    #[inline(always)]
+    ///Creates new instance, that outputs each line as separate console entry.
+    ///
+    ///On flush buffered text is split by `\n` and each line is written via own console call.
+    ///When buffer overflows, only complete lines are written, while trailing partial line is kept in buffer.
+    ///Line, that doesn't fit buffer, is written in chunks of buffer capacity.
+    pub const fn line_split(typ: ConsoleType) -> Self {
+        Self::sized(typ).split_lines()
+    }
+
+    #[inline(always)]
+    ///Creates new instance, that flushes on each newline as soon as it is written.
+    ///
+    ///Flushed message includes newline, while trailing partial line is kept in buffer until next newline or flush.
+    pub const fn streaming(typ: ConsoleType) -> Self {
+        Self::sized(typ).streamed()
+    }
+
+    #[inline(always)]
+    ///Creates new instance, that converts `\r\n` and lone `\r` into `\n` as text is written.
+    ///
+    ///`\r\n` is converted into single `\n` even if it is split across two writes.
+    pub const fn normalize_newlines(typ: ConsoleType) -> Self {
+        Self::sized(typ).normalized()
+    }
+
+    #[inline(always)]
+    ///Creates new instance, that prefixes each console entry with global sequence number `#N `.
+    ///
+    ///Number is shared by all such instances and assigned on flush, so it reflects order of emission
+    ///even if messages are built concurrently by async tasks.
+    ///Fixed buffer reserves 12 bytes of capacity for the number.
+    ///
+    ///Combined with `line_split` number is assigned per flush, not per line,
+    ///so only the first line of each flush is prefixed with it.
+    pub const fn with_seq(typ: ConsoleType) -> Self {
+        Self::sized(typ).numbered()
+    }
+
+    #[inline(always)]
+    ///Creates new instance, that appends `\n` on flush, unless text already ends with it.
+    ///
+    ///Each console entry is terminated by single newline, which is useful for sinks, that concatenate entries.
+    ///Fixed buffer reserves 1 byte of capacity for the newline.
+    pub const fn with_newline(typ: ConsoleType) -> Self {
+        Self::sized(typ).newline_terminated()
+    }
+
+    #[inline(always)]
+    ///Creates new instance, that discards text, which cannot fit buffer, instead of flushing it.
+    ///
+    ///Each message is written via single console call, truncated to buffer capacity at character boundary.
+    ///Once text is truncated, all further writes are discarded and truncation is reported by `was_truncated`,
+    ///until `flush` or `clear`.
+    ///With `alloc` feature buffer grows on demand, hence nothing is discarded.
+    pub const fn new_saturating(typ: ConsoleType) -> Self {
+        Self::sized(typ).saturated()
+    }
+
+    #[inline(always)]
+    ///Creates new instance, that truncates each message to `max` bytes, appending `…` if truncated.
+    ///
+    ///Message is everything written until `flush`, even if it is split by buffer overflow,
+    ///so once limit is reached, rest of message is discarded instead of being written via further console calls.
+    ///Limit applies to written text, excluding prefix, and is cut at character boundary.
+    pub const fn with_max_len(typ: ConsoleType, max: usize) -> Self {
+        Self::sized(typ).max_len(max)
+    }
+
+    #[inline(always)]
+    ///Creates new instance, that writes messages into `sink` instead of console.
+    ///
+    ///This takes precedence over global sink.
+    pub const fn with_sink(typ: ConsoleType, sink: &'static dyn Sink) -> Self {
+        Self::sized(typ).sink(sink)
+    }
+
+    #[inline(always)]
+    ///Creates new instance with `ConsoleType::Error`
+    pub const fn error() -> Self {
+        Self::new(ConsoleType::Error)
+    }
+
+    #[inline(always)]
+    ///Creates new instance with `ConsoleType::Warn`
+    pub const fn warn() -> Self {
+        Self::new(ConsoleType::Warn)
+    }
+
+    #[inline(always)]
+    ///Creates new instance with `ConsoleType::Info`
+    pub const fn info() -> Self {
+        Self::new(ConsoleType::Info)
+    }
+
+    #[inline(always)]
+    ///Creates new instance with `ConsoleType::Log`
+    pub const fn log() -> Self {
+        Self::new(ConsoleType::Log)
+    }
+
+    #[inline(always)]
+    ///Creates new instance with `ConsoleType::Dir`
+    pub const fn dir() -> Self {
+        Self::new(ConsoleType::Dir)
+    }
+
+    #[inline(always)]
+    ///Creates new instance with `ConsoleType::Debug`
+    pub const fn debug() -> Self {
+        Self::new(ConsoleType::Debug)
+    }
+
+    #[inline(always)]
+    ///Creates new instance with `ConsoleType::Trace`
+    pub const fn trace() -> Self {
+        Self::new(ConsoleType::Trace)
+    }
+
+    #[inline(always)]
+    ///Creates new instance, that applies `css` style to each message.
+    ///
+    ///Each message is prefixed with `%c` directive and `css` is passed as its argument.
+    ///Styling works only in browsers, other environments may either ignore it or print it as it is.
+    pub const fn styled(typ: ConsoleType, css: &'static str) -> Self {
+        Self::sized(typ).style(css)
+    }
+
+    #[cfg(feature = "timestamp")]
+    #[inline(always)]
+    ///Creates new instance, that prefixes each message with `[<ms>] ` timestamp.
+    ///
+    ///Timestamp is taken from `performance.now()` on the first write after creation or flush.
+    pub const fn with_timestamp(typ: ConsoleType) -> Self {
+        Self::sized(typ).timestamped()
+    }
+
+    #[cfg(feature = "timestamp")]
+    #[inline(always)]
+    ///Creates new instance, that writes at most `max_per_second` messages per second.
+    ///
+    ///Messages over limit are discarded on flush, using `performance.now()` to track time.
+    ///Number of discarded messages is reported as `… N messages suppressed`
+    ///on the first flush in the next second or on `Drop`.
+    ///
+    ///Limit of 0 disables rate limiting.
+    pub const fn with_rate_limit(typ: ConsoleType, max_per_second: u32) -> Self {
+        Self::sized(typ).rate_limited(max_per_second)
+    }
+}
+
+impl<const N: usize> Console<N> {
+    ///Buffer capacity in bytes, 4096 for default `Console`.
+    ///
+    ///With `alloc` feature buffer grows on demand instead, hence it is not enforced.
+    pub const CAPACITY: usize = N;
+
+    //Evaluated only when referenced, failing compilation if capacity cannot fit single character.
+    const CAPACITY_CHECK: () = core::assert!(N >= 4, "Console capacity must be at least 4 bytes to fit any UTF-8 character");
+
+    ///Creates new instance with buffer capacity of `N` bytes.
+    ///
+    ///This is `Console::new` for custom capacity, e.g. `Console::<256>::sized(ConsoleType::Info)`.
+    ///Default value of `N` is not used by type inference, so if `new` and other constructors were generic over `N`,
+    ///each `Console::new(..)` would fail to compile without type annotation.
+    ///Hence they only create instances of default capacity, while `Console::<256>::new` is not available.
+    pub const fn sized(typ: ConsoleType) -> Self {
+        Self {
+            typ,
+            #[cfg(not(feature = "alloc"))]
+            buffer: mem::MaybeUninit::uninit(),
+            #[cfg(feature = "alloc")]
+            buffer: alloc::vec::Vec::new(),
+            len: 0,
+            style: "",
+            prefix: "",
+            trim_newline: false,
+            line_split: false,
+            streaming: false,
+            normalize_newlines: false,
+            after_cr: false,
+            seq: false,
+            append_newline: false,
+            saturating: false,
+            is_saturated: false,
+            overflow_count: 0,
+            max_len: usize::MAX,
+            message_len: 0,
+            is_truncated: false,
+            sink: None,
+            use_palette: true,
+            #[cfg(feature = "std")]
+            partial: [0; 4],
+            #[cfg(feature = "std")]
+            partial_len: 0,
+            #[cfg(feature = "timestamp")]
+            timestamp: false,
+            #[cfg(feature = "timestamp")]
+            rate_limit: RateLimit {
+                max: 0,
+                window_start: 0.0,
+                count: 0,
+                suppressed: 0,
+            },
+        }
+    }
+
+    ///Creates new instance, rejecting capacity less than 4 bytes at compile time.
+    ///
+    ///Smaller buffer cannot fit every UTF-8 character, so such characters are written directly bypassing buffer.
+    ///Check is performed with `alloc` feature too, even though it ignores `N`,
+    ///so that code, which compiles, doesn't depend on enabled features.
+    ///
+    ///```rust,compile_fail
+    ///let writer = web_log::Console::<2>::new_const_checked(web_log::ConsoleType::Info);
+    ///```
+    pub const fn new_const_checked(typ: ConsoleType) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::CAPACITY_CHECK;
+        Self::sized(typ)
+    }
+
+    //Opts out of `colored` palette, for output, that cannot be styled.
+    const fn without_palette(mut self) -> Self {
+        self.use_palette = false;
+        self
+    }
+
+    //Makes instance start each message with `prefix`, as `with_prefix`.
+    const fn prefixed(mut self, prefix: &'static str) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    //Makes instance remove single trailing newline on flush, as `new_trimmed`.
+    const fn trimmed(mut self) -> Self {
+        self.trim_newline = true;
+        self
+    }
+
+    //Makes instance output each line as separate console entry, as `line_split`.
+    const fn split_lines(mut self) -> Self {
+        self.line_split = true;
+        self
+    }
+
+    //Makes instance flush on each newline, as `streaming`.
+    const fn streamed(mut self) -> Self {
+        self.streaming = true;
+        self
+    }
+
+    //Makes instance convert `\r\n` and lone `\r` into `\n`, as `normalize_newlines`.
+    const fn normalized(mut self) -> Self {
+        self.normalize_newlines = true;
+        self
+    }
+
+    //Makes instance prefix each console entry with global sequence number, as `with_seq`.
+    const fn numbered(mut self) -> Self {
+        self.seq = true;
+        self
+    }
+
+    //Makes instance append `\n` on flush, as `with_newline`.
+    const fn newline_terminated(mut self) -> Self {
+        self.append_newline = true;
+        self
+    }
+
+    //Makes instance discard text, which cannot fit buffer, as `new_saturating`.
+    const fn saturated(mut self) -> Self {
+        self.saturating = true;
+        self
+    }
+
+    //Makes instance truncate each message to `max` bytes, as `with_max_len`.
+    const fn max_len(mut self, max: usize) -> Self {
+        self.max_len = max;
+        self
+    }
+
+    //Makes instance write messages into `sink`, as `with_sink`.
+    const fn sink(mut self, sink: &'static dyn Sink) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    //Makes instance apply `css` style to each message, as `styled`.
+    const fn style(mut self, css: &'static str) -> Self {
+        self.style = css;
+        self
+    }
+
+    #[cfg(feature = "timestamp")]
+    //Makes instance prefix each message with timestamp, as `with_timestamp`.
+    const fn timestamped(mut self) -> Self {
+        self.timestamp = true;
+        self
+    }
+
+    #[cfg(feature = "timestamp")]
+    //Makes instance write at most `max_per_second` messages per second, as `with_rate_limit`.
+    const fn rate_limited(mut self, max_per_second: u32) -> Self {
+        self.rate_limit.max = max_per_second;
+        self
+    }
+
+    #[cfg(feature = "timestamp")]
+    //Returns whether message can be written now.
+    fn check_rate_limit(&mut self) -> bool {
+        let now = performance_now();
+        if now - self.rate_limit.window_start >= RATE_LIMIT_WINDOW {
+            self.report_suppressed();
+            self.rate_limit.window_start = now;
+            self.rate_limit.count = 0;
+        }
+
+        if self.rate_limit.count < self.rate_limit.max {
+            self.rate_limit.count += 1;
+            true
+        } else {
+            self.rate_limit.suppressed += 1;
+            false
+        }
+    }
+
+    #[cfg(feature = "timestamp")]
+    fn report_suppressed(&mut self) {
+        if self.rate_limit.suppressed > 0 {
+            let mut summary = Console::<64>::sized(self.typ);
+            summary.sink = self.sink;
+            //Style directive is written on first write, which `TruncatingWriter` bypasses.
+            summary.write_prefix();
+            let _ = fmt::Write::write_fmt(&mut TruncatingWriter(&mut summary), format_args!("… {} messages suppressed", self.rate_limit.suppressed));
+            self.rate_limit.suppressed = 0;
+        }
+    }
+
+    #[inline(always)]
+    ///Returns content of written buffer.
+    pub fn buffer(&self) -> &[u8] {
+        unsafe {
+            core::slice::from_raw_parts(self.buffer.as_ptr().cast(), self.len)
+        }
+    }
+
+    #[inline(always)]
+    ///Returns content of written buffer as text.
+    ///
+    ///Buffer always contains valid UTF-8: safe writes copy only complete characters
+    ///and `write_utf8_unchecked` requires caller to uphold it.
+    pub fn buffer_str(&self) -> &str {
+        unsafe {
+            core::str::from_utf8_unchecked(self.buffer())
+        }
+    }
+
+    #[inline(always)]
+    ///Returns content of written buffer for in-place editing.
+    ///
+    ///# Safety
+    ///
+    ///Content must remain valid UTF-8, as buffer content is passed to console as `str`.
+    ///Use `buffer_str_mut` for checked editing.
+    pub unsafe fn buffer_mut(&mut self) -> &mut [u8] {
+        core::slice::from_raw_parts_mut(self.as_mut_ptr(), self.len)
+    }
+
+    #[inline(always)]
+    ///Returns content of written buffer as text for in-place editing.
+    pub fn buffer_str_mut(&mut self) -> &mut str {
+        unsafe {
+            core::str::from_utf8_unchecked_mut(self.buffer_mut())
+        }
+    }
+
+    #[inline(always)]
+    ///Returns number of buffered bytes.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline(always)]
+    ///Returns whether buffer is empty, meaning `flush` would not write anything.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    ///Returns buffer capacity.
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    ///Returns currently allocated buffer capacity.
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+
+    #[inline(always)]
+    ///Returns whether current message is truncated by `new_saturating` or `with_max_len` limit.
+    ///
+    ///Reset on `flush`.
+    pub const fn was_truncated(&self) -> bool {
+        self.is_saturated || self.is_truncated
+    }
+
+    #[inline(always)]
+    ///Returns number of flushes caused by buffer overflow, which split messages.
+    ///
+    ///Counted since creation or `reset`, always 0 with `alloc` feature.
+    pub const fn overflow_count(&self) -> u32 {
+        self.overflow_count
+    }
+
+    #[cfg(feature = "bench")]
+    #[inline(always)]
+    ///Returns number of flushes forced by buffer overflow, same as `overflow_count`.
+    pub const fn force_flush_count(&self) -> u32 {
+        self.overflow_count
+    }
+
+    #[inline]
+    ///Returns number of bytes that can be written before buffer overflows.
+    pub fn remaining(&self) -> usize {
+        self.capacity().saturating_sub(self.len + self.reserved())
+    }
+
+    #[inline(always)]
+    //Returns capacity reserved for data inserted on flush.
+    fn reserved(&self) -> usize {
+        if cfg!(feature = "alloc") {
+            return 0;
+        }
+
+        let seq = if self.seq { SEQ_PREFIX_MAX } else { 0 };
+        seq + self.append_newline as usize
+    }
+
+    ///Returns uninitialized part of buffer, allowing to write into it directly.
+    ///
+    ///Written bytes must be committed via `advance`, while buffer is left unchanged otherwise.
+    ///
+    ///With `alloc` feature only currently allocated capacity is returned,
+    ///reserving `CAPACITY` bytes if there is none.
+    pub fn spare_capacity_mut(&mut self) -> &mut [core::mem::MaybeUninit<u8>] {
+        #[cfg(feature = "alloc")]
+        if self.remaining() == 0 {
+            self.buffer.reserve(self.len + N);
+        }
+
+        let remaining = self.remaining();
+        unsafe {
+            core::slice::from_raw_parts_mut(self.as_mut_ptr().add(self.len).cast(), remaining)
+        }
+    }
+
+    ///Commits `len` bytes written into `spare_capacity_mut`.
+    ///
+    ///If buffer was empty, new message is started, so its prefix is inserted before committed bytes.
+    ///Prefix takes capacity left after them, hence it is truncated if there is not enough of it.
+    ///
+    ///# Safety
+    ///
+    ///`len` bytes must be initialized with valid UTF-8, and must not exceed `remaining`.
+    ///Both are checked in debug builds.
+    pub unsafe fn advance(&mut self, len: usize) {
+        debug_assert!(len <= self.remaining());
+        debug_assert!(core::str::from_utf8(core::slice::from_raw_parts(self.as_mut_ptr().add(self.len), len)).is_ok());
+        let is_new_message = self.len == 0;
+        self.len += len;
+
+        if is_new_message && len > 0 {
+            //Prefix is written after data and then moved in front of it.
+            self.write_prefix();
+            let prefix_len = self.len - len;
+            core::slice::from_raw_parts_mut(self.as_mut_ptr(), self.len).rotate_right(prefix_len);
+        }
+    }
+
+    #[inline(always)]
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.buffer.as_mut_ptr().cast()
+    }
+
+    #[inline]
+    ///Changes type of console, flushing data written so far using old type.
+    pub fn set_type(&mut self, typ: ConsoleType) {
+        self.flush();
+        self.typ = typ;
+    }
+
+    #[inline]
+    ///Finishes current message and returns instance into its initial state with `typ`.
+    ///
+    ///Pending data is flushed using old type, so nothing is lost, unlike `clear`.
+    ///Unlike `set_type` it also discards all state of previous messages, so that instance (and its buffer allocation with `alloc`)
+    ///can be recycled instead of creating new one, keeping only its configuration such as prefix or style:
+    ///- `overflow_count` starts from 0;
+    ///- incomplete UTF-8 sequence of `io::Write` is written as `U+FFFD`, same as on drop.
+    pub fn reset(&mut self, typ: ConsoleType) {
+        #[cfg(feature = "std")]
+        if self.partial_len > 0 {
+            self.partial_len = 0;
+            self.write_text("\u{FFFD}");
+        }
+        self.flush();
+        self.overflow_count = 0;
+        self.typ = typ;
+    }
+
+    #[inline(always)]
+    ///Discards buffered data without printing it.
+    ///
+    ///Type is kept unchanged, use `reset` to flush and change type.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.reset_message();
+        #[cfg(feature = "std")]
+        {
+            self.partial_len = 0;
+        }
+    }
+
+    #[inline(always)]
+    //Resets state of current message, so that next write starts new one.
+    fn reset_message(&mut self) {
+        self.message_len = 0;
+        self.is_truncated = false;
+        self.is_saturated = false;
+        self.after_cr = false;
+    }
+
+    #[inline]
+    ///Passes buffered text with console's type into `sink` instead of printing it.
+    ///
+    ///Buffer is reset, so next write starts new message, as after `flush`.
+    ///Nothing is passed if buffer is empty.
+    pub fn drain_to<F: FnOnce(ConsoleType, &str)>(&mut self, sink: F) {
+        if self.len > 0 {
+            let typ = self.typ;
+            sink(typ, self.take());
+        }
+    }
+
+    #[inline]
+    ///Takes buffered text without printing it.
+    ///
+    ///Buffer is reset, so next write starts new message, as after `flush`.
+    pub fn take(&mut self) -> &str {
+        let len = self.len;
+        self.len = 0;
+        self.reset_message();
+        unsafe {
+            let bytes = core::slice::from_raw_parts(self.buffer.as_ptr().cast(), len);
+            core::str::from_utf8_unchecked(bytes)
+        }
+    }
+
+    #[inline]
+    ///Copies buffered text into `out` without printing it, returning number of copied bytes.
+    ///
+    ///If `out` is too small, text is cut at character boundary, so copied bytes are always valid UTF-8.
+    ///Buffer is left unchanged.
+    pub fn copy_out(&self, out: &mut [u8]) -> usize {
+        copy_utf8_truncated(out, self.buffer())
+    }
+
+    #[cfg(feature = "alloc")]
+    ///Returns buffered bytes without printing them.
+    ///
+    ///Nothing is written on drop, including truncation marker of `with_max_len` and rate limit report.
+    pub fn into_inner(self) -> alloc::vec::Vec<u8> {
+        let this = core::mem::ManuallyDrop::new(self);
+        let mut buffer = unsafe {
+            ptr::read(&this.buffer)
+        };
+        //Vec's length is always 0, while data is tracked by `len`.
+        unsafe {
+            buffer.set_len(this.len);
+        }
+        buffer
+    }
+
+    #[inline(always)]
+    ///Flushes internal buffer, if any data is available.
+    ///
+    ///Namely it dumps stored data in buffer via Console.
+    ///And resets buffered length to 0.
+    pub fn flush(&mut self) {
+        if self.is_truncated {
+            self.is_truncated = false;
+            self.write_chunk("…");
+        }
+        self.reset_message();
+
+        self.flush_buffer();
+    }
+
+    #[inline]
+    ///Flushes buffer only if it is full, meaning no more data can be written without overflow.
+    ///
+    ///Allows to write in a loop, amortizing console calls without flushing partially filled buffer.
+    ///With `alloc` feature buffer grows on demand, so it is never considered full.
+    pub fn flush_if_full(&mut self) {
+        if cfg!(not(feature = "alloc")) && self.len > 0 && self.remaining() == 0 {
+            self.flush_buffer();
+        }
+    }
+
+    #[inline(always)]
+    //Flushes buffer without finishing current message.
+    fn flush_buffer(&mut self) {
+        if self.len > 0 {
+            self.inner_flush();
+        }
+    }
+
+    fn inner_flush(&mut self) {
+        #[cfg(feature = "timestamp")]
+        if self.rate_limit.max > 0 && !self.check_rate_limit() {
+            self.len = 0;
+            return;
+        }
+
+        if self.seq {
+            self.write_seq();
+        }
+        if self.append_newline && !self.buffer().ends_with(b"\n") {
+            #[cfg(feature = "alloc")]
+            self.buffer.reserve(self.len + 1);
+            //Fixed buffer reserves capacity for newline.
+            unsafe {
+                self.as_mut_ptr().add(self.len).write(b'\n');
+            }
+            self.len += 1;
+        }
+
+        let mut text = self.buffer_str();
+        if self.trim_newline {
+            if let Some(trimmed) = text.strip_suffix('\n') {
+                text = trimmed.strip_suffix('\r').unwrap_or(trimmed);
+            }
+        }
+
+        if self.line_split {
+            text.split_terminator('\n').for_each(|line| self.output_line(line));
+        } else {
+            self.output(text, self.is_styled());
+        }
+
+        self.len = 0;
+    }
+
+    #[inline]
+    fn output(&self, text: &str, is_styled: bool) {
+        let css = match is_styled {
+            true => Some(self.css()),
+            false => None,
+        };
+        output_text(self.typ, self.sink, text, css);
+    }
+
+    #[inline]
+    fn output_line(&self, line: &str) {
+        //Only first line carries style directive.
+        self.output(line, self.is_styled() && line.starts_with("%c"));
+    }
+
+    //Flushes due to buffer overflow.
+    fn overflow_flush(&mut self) {
+        self.overflow_count = self.overflow_count.saturating_add(1);
+        if !self.line_split {
+            return self.flush_buffer();
+        }
+
+        if !self.buffer().contains(&b'\n') {
+            return self.flush_buffer();
+        }
+
+        if self.seq {
+            self.write_seq();
+        }
+        let last_line_end = match self.buffer().iter().rposition(|byte| *byte == b'\n') {
+            Some(idx) => idx,
+            None => return self.flush_buffer(),
+        };
+
+        let text = &self.buffer_str()[..last_line_end];
+        text.split('\n').for_each(|line| self.output_line(line));
+
+        let partial_start = last_line_end + 1;
+        let partial_len = self.len - partial_start;
+        unsafe {
+            let ptr = self.as_mut_ptr();
+            ptr::copy(ptr.add(partial_start), ptr, partial_len);
+        }
+        self.len = partial_len;
+    }
+
+    //Inserts sequence number at the start of buffer, after style directive.
+    fn write_seq(&mut self) {
+        let mut seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let mut prefix = [0u8; SEQ_PREFIX_MAX];
+        let mut idx = SEQ_PREFIX_MAX - 1;
+        prefix[idx] = b' ';
+        loop {
+            idx -= 1;
+            prefix[idx] = b'0' + (seq % 10) as u8;
+            seq /= 10;
+            if seq == 0 {
+                break;
+            }
+        }
+        idx -= 1;
+        prefix[idx] = b'#';
+        let prefix = &prefix[idx..];
+
+        #[cfg(feature = "alloc")]
+        self.buffer.reserve(self.len + prefix.len());
+        #[cfg(not(feature = "alloc"))]
+        if self.len + prefix.len() > N {
+            return;
+        }
+
+        let start = match self.is_styled() && self.buffer().starts_with(b"%c") {
+            true => 2,
+            false => 0,
+        };
+        unsafe {
+            let ptr = self.as_mut_ptr();
+            ptr::copy(ptr.add(start), ptr.add(start + prefix.len()), self.len - start);
+            ptr::copy_nonoverlapping(prefix.as_ptr(), ptr.add(start), prefix.len());
+        }
+        self.len += prefix.len();
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn copy_data<'a>(&mut self, text: &'a [u8]) -> &'a [u8] {
+        //Vec's length is always 0, so it is enough to reserve total length.
+        self.buffer.reserve(self.len + text.len());
+
+        unsafe {
+            ptr::copy_nonoverlapping(text.as_ptr(), self.as_mut_ptr().add(self.len), text.len());
+        }
+        self.len += text.len();
+        &text[text.len()..]
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn copy_data<'a>(&mut self, text: &'a [u8]) -> &'a [u8] {
+        let write_len = utf8_truncated_len(text, N.saturating_sub(self.len + self.reserved()));
+
+        unsafe {
+            ptr::copy_nonoverlapping(text.as_ptr(), self.as_mut_ptr().add(self.len), write_len);
+        }
+        self.len += write_len;
+        &text[write_len..]
+    }
+
+    #[inline(always)]
+    //Returns whether text is copied as it is, without any mode, that processes it in `write_text`.
+    fn is_plain(&self) -> bool {
+        !cfg!(all(not(test), feature = "disable"))
+            && self.max_len == usize::MAX
+            && !self.streaming
+            && !self.normalize_newlines
+            && !self.saturating
+    }
+
+    #[inline(always)]
+    fn is_styled(&self) -> bool {
+        !self.css().is_empty() && self.typ.as_styled_fn().is_some()
+    }
+
+    #[inline]
+    //Returns own style or, with `colored` feature, style of console's type.
+    fn css(&self) -> &'static str {
+        #[cfg(all(feature = "colored", not(feature = "node")))]
+        if self.style.is_empty() && self.use_palette && is_palette_applied() {
+            return style(self.typ);
+        }
+
+        self.style
+    }
+
+    #[inline]
+    fn write_prefix(&mut self) {
+        if self.is_styled() {
+            self.copy_data(b"%c");
+        }
+
+        #[cfg(feature = "timestamp")]
+        if self.timestamp {
+            let _ = fmt::Write::write_fmt(&mut TruncatingWriter(self), format_args!("[{:.3}] ", performance_now()));
+        }
+
+        self.copy_data(self.prefix.as_bytes());
+    }
+
+    fn write_text(&mut self, text: &str) -> usize {
+        if cfg!(all(not(test), feature = "disable")) {
+            return text.len();
+        }
+
+        if self.normalize_newlines {
+            self.write_normalized(text)
+        } else {
+            self.write_limited(text)
+        }
+    }
+
+    //Writes text with `\r\n` and lone `\r` replaced by `\n`.
+    fn write_normalized(&mut self, mut text: &str) -> usize {
+        let mut written = 0;
+
+        //Previous write ended with `\r`, that is already written as `\n`.
+        if core::mem::take(&mut self.after_cr) {
+            if let Some(rest) = text.strip_prefix('\n') {
+                written += 1;
+                text = rest;
+            }
+        }
+
+        while let Some(idx) = text.find('\r') {
+            written += self.write_limited(&text[..idx]);
+            written += self.write_limited("\n");
+            text = &text[idx + 1..];
+
+            if text.is_empty() {
+                self.after_cr = true;
+            } else if let Some(rest) = text.strip_prefix('\n') {
+                written += 1;
+                text = rest;
+            }
+        }
+
+        written + self.write_limited(text)
+    }
+
+    fn write_limited(&mut self, text: &str) -> usize {
+        let discarded = text.len();
+        let text = self.limit_text(text);
+        let discarded = discarded - text.len();
+
+        let written = if self.streaming {
+            let mut written = 0;
+            for line in text.split_inclusive('\n') {
+                written += self.write_chunk(line);
+                if line.ends_with('\n') {
+                    self.flush_buffer();
+                }
+            }
+            written
+        } else {
+            self.write_chunk(text)
+        };
+        written + discarded
+    }
+
+    //Returns part of `text` that fits `max_len` of current message.
+    fn limit_text<'a>(&mut self, text: &'a str) -> &'a str {
+        if self.max_len == usize::MAX {
+            return text;
+        }
+
+        let allowed = self.max_len - self.message_len;
+        if text.len() <= allowed {
+            self.message_len += text.len();
+            text
+        } else {
+            let mut end = allowed;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            self.message_len = self.max_len;
+            self.is_truncated = true;
+            &text[..end]
+        }
+    }
+
+    fn write_chunk(&mut self, text: &str) -> usize {
+        //Rest of truncated message is discarded, even if it could fit.
+        if self.is_saturated {
+            return text.len();
+        }
+
+        write_buffered(self, text)
+    }
+
+    #[inline]
+    ///Writes supplied text to the buffer.
+    ///
+    ///Same as `fmt::Write::write_str`, but doesn't require to import trait.
+    pub fn write_str(&mut self, text: &str) {
+        self.write_text(text);
+    }
+
+    #[inline]
+    ///Appends buffered text of `other` as it is, leaving `other` unchanged.
+    ///
+    ///Text is written using type of `self`, while type of `other` is ignored.
+    ///Buffer of `other` includes its prefix, if any, so it is appended too.
+    ///On overflow data is written same as with `write_data`.
+    pub fn merge<const M: usize>(&mut self, other: &Console<M>) {
+        self.write_data(other.buffer());
+    }
+
+    #[inline]
+    ///Writes formatted text to the buffer.
+    ///
+    ///Same as `fmt::Write::write_fmt`, but doesn't require to import trait, nor allocates intermediate `String`.
+    ///
+    ///```rust,no_run
+    ///use web_log::{Console, ConsoleType};
+    ///
+    ///let mut writer = Console::new(ConsoleType::Info);
+    ///writer.write_args(format_args!("{}", 1));
+    ///```
+    pub fn write_args(&mut self, args: fmt::Arguments<'_>) {
+        let _ = fmt::Write::write_fmt(self, args);
+    }
+
+    #[inline]
+    ///Writes `value` formatted via `Display`, returning instance back for chaining.
+    ///
+    ///```rust,no_run
+    ///use web_log::{Console, ConsoleType};
+    ///
+    ///Console::new(ConsoleType::Info).write_display(&1).write_display("!").flush();
+    ///```
+    pub fn write_display<T: fmt::Display + ?Sized>(&mut self, value: &T) -> &mut Self {
+        self.write_args(format_args!("{}", value));
+        self
+    }
+
+    #[inline]
+    ///Writes `value` formatted via `Debug`, returning instance back for chaining.
+    pub fn write_debug<T: fmt::Debug + ?Sized>(&mut self, value: &T) -> &mut Self {
+        self.write_args(format_args!("{:?}", value));
+        self
+    }
+
+    #[inline(always)]
+    ///Returns writer borrowing this instance, hiding concrete type.
+    ///
+    ///Useful to pass into code, that accepts any `fmt::Write`.
+    pub fn writer(&mut self) -> impl fmt::Write + '_ {
+        self
+    }
+
+    #[inline]
+    ///Writes formatted text and returns instance back, allowing to write and flush in single expression.
+    ///
+    ///```rust,no_run
+    ///use web_log::{Console, ConsoleType};
+    ///
+    ///let typ = ConsoleType::Warn;
+    ///Console::new(typ).write_fmt_chained(format_args!("Warning {}", 1));
+    ///```
+    pub fn write_fmt_chained(mut self, args: fmt::Arguments<'_>) -> Self {
+        self.write_args(args);
+        self
+    }
+
+    #[inline]
+    ///Writes supplied bytes to the buffer without validating them.
+    ///
+    ///# Safety
+    ///
+    ///`data` must be valid UTF-8, as buffer content is passed to console as `str`.
+    pub unsafe fn write_utf8_unchecked(&mut self, data: &[u8]) {
+        self.write_text(core::str::from_utf8_unchecked(data));
+    }
+
+    ///Writes supplied text to the buffer, returning number of consumed bytes.
+    ///
+    ///On buffer overflow, data is logged via `Console`
+    ///and buffer is filled with the rest of `data`
+    ///
+    ///Invalid UTF-8 sequences are replaced with `U+FFFD`, counting as consumed.
+    ///Therefore all of `data` is always consumed.
+    pub fn write_data(&mut self, data: &[u8]) -> usize {
+        write_lossy(data, |text| self.write_text(text))
+    }
+
+    ///Writes supplied text to the buffer followed by newline.
+    ///
+    ///Newline is never separated from the end of `data`:
+    ///if it cannot fit, last character is moved into next message together with newline.
+    ///With `new_saturating` message is never split, so newline, that cannot fit, is discarded instead.
+    ///
+    ///Invalid UTF-8 sequences are replaced as by `write_data`.
+    pub fn write_line(&mut self, data: &[u8]) {
+        self.write_data(data);
+
+        #[cfg(not(feature = "alloc"))]
+        if self.remaining() == 0 && !self.saturating {
+            let last_start = self.buffer_str().char_indices().next_back().map_or(0, |(idx, _)| idx);
+            let last_len = self.len - last_start;
+            let mut last = [0u8; 4];
+            last[..last_len].copy_from_slice(&self.buffer()[last_start..]);
+
+            self.len = last_start;
+            //Moved character is written again, so it must not count against `with_max_len` twice.
+            self.message_len = self.message_len.saturating_sub(last_len);
+            self.overflow_count = self.overflow_count.saturating_add(1);
+            self.flush_buffer();
+            self.write_text(unsafe {
+                core::str::from_utf8_unchecked(&last[..last_len])
+            });
+        }
+
+        self.write_text("\n");
+    }
+
+    ///Writes supplied text to the buffer only if it fits, never flushing on overflow.
+    ///
+    ///With `streaming` completed lines are still flushed as soon as they are written.
+    ///Invalid UTF-8 sequences are replaced as by `write_data`, which is accounted for.
+    ///Prefix of new message counts against capacity too.
+    ///On error buffer is left untouched.
+    ///
+    ///With `alloc` feature buffer grows, hence it never fails.
+    pub fn try_write_data(&mut self, data: &[u8]) -> Result<(), CapacityError> {
+        #[cfg(not(feature = "alloc"))]
+        {
+            let prev_len = self.len;
+            if prev_len == 0 {
+                self.write_prefix();
+            }
+
+            let needed = self.len - prev_len + lossy_len(data);
+            let available = N.saturating_sub(prev_len + self.reserved());
+            if needed > available {
+                self.len = prev_len;
+                return Err(CapacityError {
+                    needed,
+                    available,
+                });
+            }
+        }
+
+        self.write_data(data);
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+//Returns length of `data` with invalid UTF-8 sequences replaced by `U+FFFD`.
+fn lossy_len(mut data: &[u8]) -> usize {
+    let mut len = 0;
+    loop {
+        match core::str::from_utf8(data) {
+            Ok(text) => break len + text.len(),
+            Err(error) => {
+                len += error.valid_up_to() + '\u{FFFD}'.len_utf8();
+                match error.error_len() {
+                    Some(error_len) => data = &data[error.valid_up_to() + error_len..],
+                    None => break len,
+                }
+            }
+        }
+    }
+}
+
+impl<const N: usize> Buffered for Console<N> {
+    #[inline(always)]
+    fn buffered_len(&self) -> usize {
+        self.len
+    }
+
+    fn copy_text(&mut self, text: &str) -> usize {
+        if self.len == 0 {
+            self.write_prefix();
+        }
+
+        let copied = text.len() - self.copy_data(text.as_bytes()).len();
+        if self.saturating && copied < text.len() {
+            self.is_saturated = true;
+            //Rest of text is discarded.
+            text.len()
+        } else {
+            copied
+        }
+    }
+
+    #[inline(always)]
+    fn overflow(&mut self) {
+        self.overflow_flush();
+    }
+
+    fn output_direct(&mut self, text: &str) {
+        //Write out prefix, if any, before the character.
+        self.flush_buffer();
+        self.output(text, false);
+    }
+}
+
+impl Console {
+    #[inline(always)]
+    ///Creates `BorrowedConsole`, that writes into `buffer` instead of owning one.
+    pub fn new_with_buffer(typ: ConsoleType, buffer: &mut [u8]) -> BorrowedConsole<'_> {
+        BorrowedConsole::new(typ, buffer)
+    }
+
+    #[cfg(feature = "test-util")]
+    ///Returns text of the most recent message flushed by current thread.
+    ///
+    ///See `captured` for details.
+    pub fn peek_last_flush() -> Option<std::string::String> {
+        CAPTURED.with(|captured| captured.borrow().back().map(|(_, text)| text.clone()))
+    }
+
+    #[inline]
+    ///Writes `value` into console as it is, without converting it to string.
+    ///
+    ///This allows to inspect objects interactively, instead of flat text.
+    pub fn log_value(typ: ConsoleType, value: &JsValue) {
+        if typ.is_enabled() {
+            (typ.as_value_fn())(value);
+        }
+    }
+}
+
+impl<const N: usize> fmt::Display for Console<N> {
+    #[inline(always)]
+    ///Writes buffered text as it is, without flushing it.
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(self.buffer_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for Console<N> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const PREVIEW_LEN: usize = 64;
+
+        let text = self.buffer_str();
+        let mut preview_len = core::cmp::min(PREVIEW_LEN, text.len());
+        while !text.is_char_boundary(preview_len) {
+            preview_len -= 1;
+        }
+
+        fmt.debug_struct("Console")
+            .field("typ", &self.typ)
+            .field("len", &self.len)
+            .field("capacity", &self.capacity())
+            .field("preview", &&text[..preview_len])
+            .finish()
+    }
+}
+
+impl<const N: usize> fmt::Write for Console<N> {
+    #[inline]
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.write_text(text);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        let mut buffer = [0u8; 4];
+        let text = ch.encode_utf8(&mut buffer);
+
+        //Single character is never split, so `copy_data` either copies it whole or nothing.
+        if !self.is_plain() || self.len == 0 || !self.copy_data(text.as_bytes()).is_empty() {
+            self.write_text(text);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+#[inline(never)]
+#[cold]
+fn invalid_utf8() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "Console accepts only valid UTF-8")
+}
+
+#[cfg(feature = "std")]
+impl<const N: usize> std::io::Write for Console<N> {
+    ///Writes valid UTF-8 prefix of `buf`.
+    ///
+    ///Incomplete UTF-8 sequence at the end of `buf` is kept until following write completes it,
+    ///so that characters split across writes are written as whole.
+    ///If it is not completed, it is replaced with `U+FFFD`.
+    ///
+    ///Fails with `InvalidData` if `buf` starts with invalid UTF-8 sequence.
+    fn write(&mut self, mut buf: &[u8]) -> std::io::Result<usize> {
+        let mut written = 0;
+
+        while self.partial_len > 0 {
+            let (byte, rest) = match buf.split_first() {
+                Some(split) => split,
+                None => return Ok(written),
+            };
+
+            self.partial[self.partial_len] = *byte;
+            match core::str::from_utf8(&self.partial[..self.partial_len + 1]) {
+                Ok(_) => {
+                    let (partial, len) = (self.partial, self.partial_len + 1);
+                    self.partial_len = 0;
+                    self.write_text(unsafe {
+                        core::str::from_utf8_unchecked(&partial[..len])
+                    });
+                },
+                Err(error) if error.error_len().is_none() => self.partial_len += 1,
+                Err(_) => {
+                    //Already consumed bytes cannot be rejected, so they are replaced as by `write_data`.
+                    self.partial_len = 0;
+                    self.write_text("\u{FFFD}");
+                    break;
+                },
+            }
+            written += 1;
+            buf = rest;
+        }
+
+        match core::str::from_utf8(buf) {
+            Ok(text) => {
+                self.write_text(text);
+                Ok(written + buf.len())
+            },
+            Err(error) => {
+                let (valid, rest) = buf.split_at(error.valid_up_to());
+                self.write_text(unsafe {
+                    core::str::from_utf8_unchecked(valid)
+                });
+                written += valid.len();
+
+                if error.error_len().is_none() {
+                    self.partial[..rest.len()].copy_from_slice(rest);
+                    self.partial_len = rest.len();
+                    Ok(written + rest.len())
+                } else if written > 0 {
+                    Ok(written)
+                } else {
+                    Err(invalid_utf8())
+                }
+            }
+        }
+    }
+
+    ///Writes each slice as by `write`, stopping at first invalid UTF-8 sequence.
+    ///
+    ///Characters split across slices are written as whole.
+    ///Fails only if nothing can be written.
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        let mut written = 0;
+
+        for buf in bufs {
+            match std::io::Write::write(self, buf) {
+                Ok(len) => {
+                    written += len;
+                    if len < buf.len() {
+                        break;
+                    }
+                },
+                Err(error) => return if written > 0 { Ok(written) } else { Err(error) },
+            }
+        }
+
+        Ok(written)
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.flush();
+        Ok(())
+    }
+}
+
+impl<const N: usize> Drop for Console<N> {
+    #[inline]
+    fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        if self.partial_len > 0 {
+            self.write_text("\u{FFFD}");
+        }
+        self.flush();
+        #[cfg(feature = "timestamp")]
+        self.report_suppressed();
+    }
+}
+
+///Console that coalesces multiple messages to reduce number of console calls.
+///
+///Messages are kept in insertion order and separated by newline.
+///Consecutive messages of the same type are written via single console call,
+///while change of type starts new call.
+///
+///Everything buffered is written on `flush` or `Drop`.
+///On buffer overflow data is written as by `Console`, hence lowering number of calls requires enough capacity.
+pub struct BatchConsole<const N: usize = BUFFER_CAPACITY> {
+    console: Console<N>,
+}
+
+impl<const N: usize> BatchConsole<N> {
+    #[inline]
+    ///Creates new empty instance.
+    pub const fn new() -> Self {
+        Self {
+            console: Console::sized(ConsoleType::Info),
+        }
+    }
+
+    ///Adds message `text` of type `typ`.
+    ///
+    ///Messages that do not pass `max_level` are discarded.
+    pub fn push(&mut self, typ: ConsoleType, text: &str) {
+        if self.start_message(typ) {
+            self.console.write_str(text);
+        }
+    }
+
+    ///Adds formatted message of type `typ`.
+    ///
+    ///Messages that do not pass `max_level` are discarded without formatting.
+    pub fn push_fmt(&mut self, typ: ConsoleType, args: fmt::Arguments<'_>) {
+        if self.start_message(typ) {
+            let _ = fmt::Write::write_fmt(&mut self.console, args);
+        }
+    }
+
+    fn start_message(&mut self, typ: ConsoleType) -> bool {
+        if !typ.is_enabled() {
+            return false;
+        }
+
+        if self.console.typ != typ {
+            self.console.set_type(typ);
+        } else if !self.console.is_empty() {
+            self.console.write_str("\n");
+        }
+        true
+    }
+
+    #[inline]
+    ///Writes all buffered messages into console.
+    pub fn flush(&mut self) {
+        self.console.flush();
+    }
+}
+
+impl<const N: usize> Default for BatchConsole<N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct SharedConsole {
+    is_locked: AtomicBool,
+    console: UnsafeCell<Console>,
+}
+
+//Access to console is serialized via `is_locked`, which is sufficient as `Console` is `Send`.
+unsafe impl Sync for SharedConsole {}
+
+static SHARED: SharedConsole = SharedConsole {
+    is_locked: AtomicBool::new(false),
+    console: UnsafeCell::new(Console::new(ConsoleType::Info)),
+};
+
+struct SharedGuard(&'static mut Console);
+
+impl Drop for SharedGuard {
+    #[inline]
+    fn drop(&mut self) {
+        //Nothing of this message may leak into the next user's one.
+        self.0.reset(self.0.typ);
+        SHARED.is_locked.store(false, Ordering::Release);
+    }
+}
+
+///Runs `cb` with shared `Console` of type `typ`, flushing it at the end.
+///
+///Single global buffer is reused across calls, avoiding to reserve new buffer on stack for each message.
+///If shared buffer is already in use (e.g. nested call or another thread), then `cb` is given new `Console` instead.
+pub fn with_writer<R, F: FnOnce(&mut Console) -> R>(typ: ConsoleType, cb: F) -> R {
+    match SHARED.is_locked.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed) {
+        Ok(_) => {
+            let guard = SharedGuard(unsafe {
+                &mut *SHARED.console.get()
+            });
+            guard.0.typ = typ;
+            cb(guard.0)
+        },
+        Err(_) => cb(&mut Console::new(typ)),
+    }
+}
+
+///`Console` alternative, that writes into borrowed buffer instead of owning it.
+///
+///Buffer can be sized at runtime and placed anywhere, e.g. in static arena.
+///Data is flushed on overflow and drop same as with `Console`, but configuration (prefix, style and etc.) is not supported.
+///Unlike `Console` it ignores `alloc` feature, hence buffer never grows.
+///
+///```rust,no_run
+///use web_log::{Console, ConsoleType};
+///
+///let mut buffer = [0u8; 128];
+///let mut writer = Console::new_with_buffer(ConsoleType::Info, &mut buffer);
+///writer.write_str("Hello World!");
+///drop(writer); //or writer.flush();
+///```
+pub struct BorrowedConsole<'a> {
+    typ: ConsoleType,
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> BorrowedConsole<'a> {
+    #[inline]
+    ///Creates new instance, that writes into `buffer`.
+    ///
+    ///Buffer content is ignored, as it is only used as storage.
+    pub fn new(typ: ConsoleType, buffer: &'a mut [u8]) -> Self {
+        Self {
+            typ,
+            buffer,
+            len: 0,
+        }
+    }
+
+    #[inline(always)]
+    ///Returns type of console.
+    pub fn typ(&self) -> ConsoleType {
+        self.typ
+    }
+
+    #[inline(always)]
+    ///Returns content of written buffer.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    #[inline(always)]
+    ///Returns content of written buffer as text.
+    pub fn buffer_str(&self) -> &str {
+        //Buffer is written only with whole characters of valid text.
+        unsafe {
+            core::str::from_utf8_unchecked(&self.buffer[..self.len])
+        }
+    }
+
+    #[inline(always)]
+    ///Returns number of buffered bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline(always)]
+    ///Returns whether buffer is empty, meaning `flush` would not write anything.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline(always)]
+    ///Returns buffer capacity.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    #[inline(always)]
+    ///Discards buffered data without printing it.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    #[inline]
+    ///Flushes internal buffer, if any data is available.
+    pub fn flush(&mut self) {
+        if self.len > 0 {
+            output_text(self.typ, None, self.buffer_str(), None);
+            self.len = 0;
+        }
+    }
+
+    fn write_text(&mut self, text: &str) -> usize {
+        if cfg!(all(not(test), feature = "disable")) {
+            return text.len();
+        }
+
+        write_buffered(self, text)
+    }
+
+    #[inline]
+    ///Writes supplied text to the buffer.
+    pub fn write_str(&mut self, text: &str) {
+        self.write_text(text);
+    }
+
+    ///Writes supplied text to the buffer, returning number of consumed bytes.
+    ///
+    ///Invalid UTF-8 sequences are replaced with `U+FFFD`, counting as consumed.
+    ///Therefore all of `data` is always consumed.
+    pub fn write_data(&mut self, data: &[u8]) -> usize {
+        write_lossy(data, |text| self.write_text(text))
+    }
+}
+
+impl Buffered for BorrowedConsole<'_> {
+    #[inline(always)]
+    fn buffered_len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn copy_text(&mut self, text: &str) -> usize {
+        let copied = copy_utf8_truncated(&mut self.buffer[self.len..], text.as_bytes());
+        self.len += copied;
+        copied
+    }
+
+    #[inline(always)]
+    fn overflow(&mut self) {
+        self.flush();
+    }
+
+    #[inline]
+    fn output_direct(&mut self, text: &str) {
+        output_text(self.typ, None, text, None);
+    }
+}
+
+impl fmt::Write for BorrowedConsole<'_> {
+    #[inline]
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.write_text(text);
+        Ok(())
+    }
+}
+
+impl Drop for BorrowedConsole<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+struct PoolSlot<const N: usize> {
+    is_locked: AtomicBool,
+    console: UnsafeCell<Console<N>>,
+}
+
+impl<const N: usize> PoolSlot<N> {
+    //Array of non-`Copy` slots can be only initialized by constant.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NEW: Self = Self {
+        is_locked: AtomicBool::new(false),
+        console: UnsafeCell::new(Console::sized(ConsoleType::Info)),
+    };
+}
+
+///Pool of `S` reusable `Console` buffers, intended to be stored in `static`.
+///
+///Each `get` takes free buffer, so that high-frequency logging (e.g. render loop) doesn't reserve new buffer on stack for each message.
+///If all buffers are in use, new `Console` is created instead.
+///
+///```rust,no_run
+///use web_log::{ConsolePool, ConsoleType};
+///
+///static POOL: ConsolePool = ConsolePool::new();
+///
+///let mut writer = POOL.get(ConsoleType::Info);
+///writer.write_str("frame");
+///drop(writer); //flushes and returns buffer into pool
+///```
+pub struct ConsolePool<const S: usize = 4, const N: usize = BUFFER_CAPACITY> {
+    slots: [PoolSlot<N>; S],
+}
+
+//Access to each console is serialized via its `is_locked`, which is sufficient as `Console` is `Send`.
+unsafe impl<const S: usize, const N: usize> Sync for ConsolePool<S, N> {}
+
+impl<const S: usize, const N: usize> ConsolePool<S, N> {
+    ///Creates new pool.
+    pub const fn new() -> Self {
+        Self {
+            slots: [PoolSlot::NEW; S],
+        }
+    }
+
+    ///Takes free `Console` of type `typ`, or creates new one if all are in use.
+    pub fn get(&self, typ: ConsoleType) -> PooledConsole<'_, N> {
+        for slot in self.slots.iter() {
+            if slot.is_locked.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+                let console = unsafe {
+                    &mut *slot.console.get()
+                };
+                console.typ = typ;
+                return PooledConsole(PooledInner::Pooled(slot, console));
+            }
+        }
+
+        PooledConsole(PooledInner::Fresh(Console::sized(typ)))
+    }
+}
+
+impl<const S: usize, const N: usize> Default for ConsolePool<S, N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum PooledInner<'a, const N: usize> {
+    Pooled(&'a PoolSlot<N>, &'a mut Console<N>),
+    Fresh(Console<N>),
+}
+
+///`Console` taken from `ConsolePool`.
+///
+///On drop it is flushed and returned into pool.
+pub struct PooledConsole<'a, const N: usize = BUFFER_CAPACITY>(PooledInner<'a, N>);
+
+impl<const N: usize> PooledConsole<'_, N> {
+    #[inline]
+    ///Returns whether buffer is taken from pool, rather than created because all buffers are in use.
+    pub fn is_pooled(&self) -> bool {
+        matches!(self.0, PooledInner::Pooled(..))
+    }
+}
+
+impl<const N: usize> core::ops::Deref for PooledConsole<'_, N> {
+    type Target = Console<N>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            PooledInner::Pooled(_, console) => console,
+            PooledInner::Fresh(console) => console,
+        }
+    }
+}
+
+impl<const N: usize> core::ops::DerefMut for PooledConsole<'_, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match &mut self.0 {
+            PooledInner::Pooled(_, console) => console,
+            PooledInner::Fresh(console) => console,
+        }
+    }
+}
+
+impl<const N: usize> fmt::Write for PooledConsole<'_, N> {
+    #[inline(always)]
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        fmt::Write::write_str(&mut **self, text)
+    }
+}
+
+impl<const N: usize> Drop for PooledConsole<'_, N> {
+    #[inline]
+    fn drop(&mut self) {
+        if let PooledInner::Pooled(slot, console) = &mut self.0 {
+            //Nothing of this message may leak into the next user's one.
+            console.reset(console.typ);
+            slot.is_locked.store(false, Ordering::Release);
+        }
+    }
+}
+
+#[inline]
+///Writes formatted message into console with specified `ConsoleType`.
+///
+///Non-macro alternative of `console!`, when `fmt::Arguments` are already available.
+pub fn write_console(typ: ConsoleType, args: fmt::Arguments<'_>) {
+    __write_console(typ, "", args);
+}
+
+#[doc(hidden)]
+pub fn __write_console(typ: ConsoleType, prefix: &'static str, args: fmt::Arguments<'_>) {
+    if typ.is_enabled() {
+        let mut writer = Console::with_prefix(typ, prefix);
+        #[cfg(all(not(test), feature = "eager"))]
+        let _ = fmt::Write::write_fmt(&mut EagerWriter(&mut writer), args);
+        #[cfg(any(test, not(feature = "eager")))]
+        let _ = fmt::Write::write_fmt(&mut writer, args);
+    }
+}
+
+#[cfg(all(not(test), feature = "eager"))]
+///Writer that flushes after each write.
+struct EagerWriter<'a>(&'a mut Console);
+
+#[cfg(all(not(test), feature = "eager"))]
+impl fmt::Write for EagerWriter<'_> {
+    #[inline]
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.0.write_str(text);
+        self.0.flush();
+        Ok(())
+    }
+}
+
+///Writes each of `values` as separate argument of `console.log`.
+///
+///Up to 8 values are supported, rest is ignored.
+pub fn log_values(values: &[JsValue]) {
+    if !ConsoleType::Log.is_enabled() {
+        return;
+    }
+
+    match values {
+        [] => (),
+        [a] => log_val(a),
+        [a, b] => log_values2(a, b),
+        [a, b, c] => log_values3(a, b, c),
+        [a, b, c, d] => log_values4(a, b, c, d),
+        [a, b, c, d, e] => log_values5(a, b, c, d, e),
+        [a, b, c, d, e, f] => log_values6(a, b, c, d, e, f),
+        [a, b, c, d, e, f, g] => log_values7(a, b, c, d, e, f, g),
+        [a, b, c, d, e, f, g, h, ..] => log_values8(a, b, c, d, e, f, g, h),
+    }
+}
+
+///Writes `value` into console using `%o` format specifier.
+///
+///Unlike `Console::log_value`, which passes value as it is, `%o` lets devtools choose its best renderer:
+///
+///- `%o` - optimally useful formatting, e.g. DOM element is shown as expandable node in elements tree;
+///- `%O` - generic JavaScript object formatting, e.g. DOM element is shown as object with its properties;
+///- `console.dir` - always shows interactive list of properties, same as `%O`.
+///
+///`ConsoleType::Dir` doesn't support format directives, therefore value is passed to `console.dir` as it is.
+pub fn log_object(typ: ConsoleType, value: &JsValue) {
+    if !typ.is_enabled() {
+        return;
+    }
+
+    match typ.as_object_fn() {
+        Some(log_fn) => log_fn("%o", value),
+        None => dir_val(value),
+    }
+}
+
+///Writes `label` followed by hex dump of `data` as single message.
+///
+///Each line contains offset, 16 bytes in hex and their ASCII representation,
+///where non-printable characters are replaced with `.`:
+///
+///```text
+///00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a        |Hello, world!.|
+///```
+///
+///If dump cannot fit buffer, it is split at line boundary.
+pub fn hexdump(typ: ConsoleType, label: &str, data: &[u8]) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    const BYTES_PER_LINE: usize = 16;
+    //Start of ASCII column, after offset, bytes in two groups of 8 and separators.
+    const ASCII_START: usize = 8 + 2 + BYTES_PER_LINE * 3 + 2;
+
+    if !typ.is_enabled() {
+        return;
+    }
+
+    let mut writer = Console::new(typ);
+    writer.write_str(label);
+
+    for (idx, chunk) in data.chunks(BYTES_PER_LINE).enumerate() {
+        let mut line = [b' '; ASCII_START + BYTES_PER_LINE + 2];
+
+        let offset = idx * BYTES_PER_LINE;
+        for digit in 0..8 {
+            line[7 - digit] = HEX[(offset >> (digit * 4)) & 0xf];
+        }
+
+        line[ASCII_START] = b'|';
+        for (pos, byte) in chunk.iter().enumerate() {
+            let hex_start = 10 + pos * 3 + pos / 8;
+            line[hex_start] = HEX[(byte >> 4) as usize];
+            line[hex_start + 1] = HEX[(byte & 0xf) as usize];
+            line[ASCII_START + 1 + pos] = match byte {
+                0x20..=0x7e => *byte,
+                _ => b'.',
+            };
+        }
+        let line_len = ASCII_START + 1 + chunk.len() + 1;
+        line[line_len - 1] = b'|';
+
+        if cfg!(not(feature = "alloc")) && writer.remaining() <= line_len {
+            writer.flush();
+        }
+        if !writer.is_empty() {
+            writer.write_str("\n");
+        }
+        writer.write_data(&line[..line_len]);
+    }
+}
+
+#[cfg(feature = "panic")]
+///Writer into fixed buffer, truncating text that cannot fit.
+struct SliceWriter<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+#[cfg(feature = "panic")]
+impl SliceWriter<'_> {
+    #[inline(always)]
+    fn as_str(&self) -> &str {
+        //Buffer is written only with whole characters of valid text.
+        unsafe {
+            core::str::from_utf8_unchecked(&self.buffer[..self.len])
+        }
+    }
+}
+
+#[cfg(feature = "panic")]
+impl fmt::Write for SliceWriter<'_> {
+    #[inline]
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.len += copy_utf8_truncated(&mut self.buffer[self.len..], text.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "panic")]
+///Installs panic hook, that writes panic message via `console.error`.
+///
+///Message is written using fixed buffer on stack, even with `alloc` feature,
+///so it is truncated if exceeds 4096 bytes.
+pub fn init_panic_hook() {
+    std::panic::set_hook(std::boxed::Box::new(|info| {
+        //Allocation may fail while panicking, hence `Console` is only used to write formatted message.
+        let console = Console::new(ConsoleType::Error);
+        let is_styled = console.is_styled();
+
+        let mut buffer = [0u8; BUFFER_CAPACITY];
+        let mut writer = SliceWriter {
+            buffer: &mut buffer,
+            len: 0,
+        };
+        if is_styled {
+            let _ = fmt::Write::write_str(&mut writer, "%c");
+        }
+        let _ = fmt::Write::write_fmt(&mut writer, format_args!("{}", info));
+        console.output(writer.as_str(), is_styled);
+    }));
+}
+
+#[must_use = "Group ends as soon as guard is dropped"]
+///Guard of console's group.
+///
+///All console output is indented until guard is dropped, which calls `console.groupEnd`.
+pub struct Group {
+    _priv: (),
+}
+
+impl Drop for Group {
+    #[inline]
+    fn drop(&mut self) {
+        group_end();
+    }
+}
+
+#[inline]
+///Starts new group with `label` via `console.group`
+pub fn group(label: &str) -> Group {
+    group_start(label);
+    Group {
+        _priv: (),
+    }
+}
+
+#[inline]
+///Starts new collapsed group with `label` via `console.groupCollapsed`
+pub fn group_collapsed(label: &str) -> Group {
+    group_collapsed_start(label);
+    Group {
+        _priv: (),
+    }
+}
+
+#[inline]
+///Increments and prints counter for `label` via `console.count`
+pub fn count(label: &str) {
+    console_count(label);
+}
+
+#[inline]
+///Resets counter for `label` via `console.countReset`
+pub fn count_reset(label: &str) {
+    console_count_reset(label);
+}
+
+#[inline]
+///Clears console via `console.clear`
+///
+///Some environments ignore it, e.g. browser's devtools with enabled "Preserve log".
+pub fn clear() {
+    console_clear();
+}
+
+#[inline]
+///Starts timer for `label` via `console.time`
+pub fn time(label: &str) {
+    console_time(label);
+}
+
+#[inline]
+///Stops timer for `label` and prints elapsed time via `console.timeEnd`
+pub fn time_end(label: &str) {
+    console_time_end(label);
+}
+
+#[must_use = "Timer stops as soon as guard is dropped"]
+///Guard of console's timer.
+///
+///Elapsed time is printed when guard is dropped, which calls `console.timeEnd`.
+pub struct Timer<'a> {
+    label: &'a str,
+}
+
+impl Drop for Timer<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        time_end(self.label);
+    }
+}
+
+#[inline]
+///Starts new timer with `label` via `console.time`
+pub fn timer(label: &str) -> Timer<'_> {
+    time(label);
+    Timer {
+        label,
+    }
+}
+
+///Starts new group with formatted label via `console.group`
+pub fn group_args(args: fmt::Arguments<'_>) -> Group {
+    //`console.group` gets no css argument, hence label must not be styled.
+    let mut writer = Console::new(ConsoleType::Log).without_palette();
+    let _ = fmt::Write::write_fmt(&mut writer, args);
+    let result = group(writer.buffer_str());
+    writer.clear();
+    result
+}
+
+///Writes formatted message via `console.assert` if `condition` is false.
+///
+///Message is formatted only when `condition` is false.
+pub fn assert_args(condition: bool, args: fmt::Arguments<'_>) {
+    if !condition {
+        __assert_args("", args);
+    }
+}
+
+#[doc(hidden)]
+#[cold]
+pub fn __assert_args(prefix: &'static str, args: fmt::Arguments<'_>) {
+    //`console.assert` gets no css argument, hence message must not be styled.
+    let mut writer = Console::with_prefix(ConsoleType::Error, prefix).without_palette();
+    let _ = fmt::Write::write_fmt(&mut writer, args);
+    console_assert(false, writer.buffer_str());
+    writer.clear();
+}
+
+#[doc(hidden)]
+#[cold]
+#[track_caller]
+pub fn __assert_failed(prefix: &'static str, op: &str, left: &dyn fmt::Debug, right: &dyn fmt::Debug, args: Option<fmt::Arguments<'_>>) {
+    if ConsoleType::Error.is_enabled() {
+        match args {
+            Some(args) => __write_console(ConsoleType::Error, prefix, format_args!("assertion `left {} right` failed: {}\n  left: {:?}\n right: {:?}", op, args, left, right)),
+            None => __write_console(ConsoleType::Error, prefix, format_args!("assertion `left {} right` failed\n  left: {:?}\n right: {:?}", op, left, right)),
+        }
+    }
+
+    #[cfg(feature = "assert-panic")]
+    panic!("assertion `left {} right` failed", op);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Console, ConsoleType, ParseConsoleTypeError};
+    use std::cell::RefCell;
+    const DATA: &str = "1234567891";
+    const MACRO_PREFIX: &str = if cfg!(feature = "module-prefix") {
+        "[web_log::tests] "
+    } else {
+        ""
+    };
+    const NEWLINE: &str = if cfg!(feature = "explicit-newline") {
+        "\n"
+    } else {
+        ""
+    };
+    const DEBUG_METHOD: &str = if cfg!(feature = "debug-as-log") {
+        "log"
+    } else {
+        "debug"
+    };
+
+    std::thread_local! {
+        static CAPTURED: RefCell<Vec<(&'static str, String)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[cfg(feature = "colored")]
+    std::thread_local! {
+        pub static PALETTE: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+    }
+
+    #[cfg(feature = "timestamp")]
+    std::thread_local! {
+        pub static NOW: std::cell::Cell<f64> = const { std::cell::Cell::new(1.5) };
+    }
+
+    pub fn capture(method: &'static str, text: &str) {
+        CAPTURED.with(|captured| captured.borrow_mut().push((method, text.to_owned())));
+    }
+
+    fn take_captured() -> Vec<(&'static str, String)> {
+        CAPTURED.with(|captured| captured.borrow_mut().split_off(0))
+    }
+
+    #[test]
+    fn should_normal_write() {
+        let mut writer = Console::new(ConsoleType::Warn);
+
+        assert_eq!(writer.typ, ConsoleType::Warn);
+
+        let data = DATA.as_bytes();
+
+        assert!(writer.is_empty());
+        writer.write_data(data);
+        assert!(!writer.is_empty());
+        assert_eq!(writer.len(), data.len());
+        assert_eq!(writer.buffer(), data);
+
+        writer.write_data(b" ");
+        writer.write_data(data);
+        let expected = format!("{} {}", DATA, DATA);
+        assert_eq!(writer.len, expected.len());
+        assert_eq!(writer.buffer(), expected.as_bytes());
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_handle_write_overflow() {
+        let mut writer = Console::new(ConsoleType::Warn);
+        let data = DATA.as_bytes();
+
+        //BUFFER_CAPACITY / DATA.len() = 148.xxx
+        for idx in 1..=409 {
+            writer.write_data(data);
+            assert_eq!(writer.len, data.len() * idx);
+        }
+
+        writer.write_data(data);
+        assert_eq!(writer.len, 4);
+        assert_eq!(writer.remaining(), writer.capacity() - 4);
+        writer.flush();
+        assert_eq!(writer.len, 0);
+        assert_eq!(writer.remaining(), 4096);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_handle_write_overflow_outside_of_char_boundary() {
+        let mut writer = Console::new(ConsoleType::Warn);
+        let data = DATA.as_bytes();
+
+        for idx in 1..=409 {
+            writer.write_data(data);
+            assert_eq!(writer.len, data.len() * idx);
+        }
+
+        writer.write_data(b"1234");
+        assert_eq!(4094, writer.len);
+        let unicode = "ロリ";
+        assert_eq!(writer.write_data(unicode.as_bytes()), unicode.len());
+        assert_eq!(writer.len, unicode.len());
+        assert_eq!(writer.buffer(), unicode.as_bytes());
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_handle_write_overflow_with_custom_capacity() {
+        let mut writer = Console::<8>::sized(ConsoleType::Warn);
+        let data = DATA.as_bytes();
+
+        writer.write_data(data);
+        assert_eq!(writer.buffer(), &data[8..]);
+
+        writer.write_data(b"123");
+        writer.write_data("ロリ".as_bytes());
+        assert_eq!(writer.buffer(), "リ".as_bytes());
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_move_split_character_into_next_flush() {
+        let mut writer = Console::<8>::sized(ConsoleType::Warn);
+
+        writer.write_data(b"123456");
+        assert_eq!(writer.write_data("ロ1".as_bytes()), 4);
+        assert_eq!(writer.buffer(), "ロ1".as_bytes());
+
+        writer.write_data(b"22");
+        assert_eq!(writer.write_data("😀".as_bytes()), 4);
+        assert_eq!(writer.buffer(), "😀".as_bytes());
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("warn", "123456".to_owned()),
+            ("warn", "ロ122".to_owned()),
+            ("warn", "😀".to_owned()),
+        ]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_make_progress_when_character_exceeds_capacity() {
+        let mut writer = Console::<2>::sized(ConsoleType::Warn);
+
+        assert_eq!(writer.write_data("1😀ロ2".as_bytes()), 9);
+        assert_eq!(writer.buffer(), b"2");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("warn", "1".to_owned()),
+            ("warn", "😀".to_owned()),
+            ("warn", "ロ".to_owned()),
+            ("warn", "2".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_count_overflows() {
+        let mut writer = Console::<8>::sized(ConsoleType::Info);
+
+        writer.write_str("1234");
+        writer.flush();
+        assert_eq!(writer.overflow_count(), 0);
+        writer.write_str("123456789");
+        writer.write_line(b"abcdefg");
+        assert_eq!(writer.overflow_count(), if cfg!(feature = "alloc") { 0 } else { 2 });
+        drop(writer);
+
+        let captured = take_captured();
+        if cfg!(feature = "alloc") {
+            assert_eq!(captured.len(), 2);
+        } else {
+            assert_eq!(captured, [
+                ("info", "1234".to_owned()),
+                ("info", "12345678".to_owned()),
+                ("info", "9abcdef".to_owned()),
+                ("info", "g\n".to_owned()),
+            ]);
+        }
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_terminate_with_tiny_capacity() {
+        let data = "😀1😀".as_bytes();
+
+        let mut writer = Console::<0>::sized(ConsoleType::Warn);
+        assert_eq!(writer.write_data(data), data.len());
+        drop(writer);
+        let mut writer = Console::<1>::sized(ConsoleType::Warn).prefixed(">");
+        assert_eq!(writer.write_data(data), data.len());
+        drop(writer);
+        let mut writer = Console::<5>::sized(ConsoleType::Warn).split_lines();
+        assert_eq!(writer.write_data(b"1\n2"), 3);
+        assert_eq!(writer.write_data(data), data.len());
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("warn", "😀".to_owned()),
+            ("warn", "1".to_owned()),
+            ("warn", "😀".to_owned()),
+            ("warn", ">".to_owned()),
+            ("warn", "😀".to_owned()),
+            ("warn", ">".to_owned()),
+            ("warn", "1".to_owned()),
+            ("warn", ">".to_owned()),
+            ("warn", "😀".to_owned()),
+            ("warn", "1".to_owned()),
+            ("warn", "2😀".to_owned()),
+            ("warn", "1😀".to_owned()),
+        ]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn should_grow_on_write_overflow() {
+        let mut writer = Console::<8>::sized(ConsoleType::Warn);
+        let data = DATA.as_bytes();
+
+        for idx in 1..=500 {
+            writer.write_data(data);
+            assert_eq!(writer.len, data.len() * idx);
+        }
+
+        assert_eq!(writer.buffer(), DATA.repeat(500).as_bytes());
+        writer.flush();
+        assert_eq!(writer.len, 0);
+    }
+
+    #[test]
+    fn should_clear_without_printing() {
+        let mut writer = Console::new(ConsoleType::Info);
+
+        writer.write_data(DATA.as_bytes());
+        writer.clear();
+        assert!(writer.buffer().is_empty());
+        writer.flush();
+        drop(writer);
+        assert!(take_captured().is_empty());
+
+        let mut writer = Console::new(ConsoleType::Info);
+        writer.write_data(DATA.as_bytes());
+        writer.clear();
+        writer.write_data(b"2");
+        drop(writer);
+        assert_eq!(take_captured(), [("info", "2".to_owned())]);
+    }
+
+    #[test]
+    fn should_write_console_args() {
+        crate::write_console(ConsoleType::Debug, format_args!("{}-{}", 1, 2));
+
+        assert_eq!(take_captured(), [
+            (DEBUG_METHOD, "1-2".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_write_via_macros() {
+        crate::console!(ConsoleType::Warn, "{} {}", 1, 2);
+        crate::println!("{}", DATA);
+        crate::eprintln!();
+        crate::warnln!("{}", 1);
+        crate::infoln!();
+        crate::debugln!("{}", 2);
+
+        assert_eq!(take_captured(), [
+            ("warn", format!("{}1 2", MACRO_PREFIX)),
+            ("info", format!("{}{}{}", MACRO_PREFIX, DATA, NEWLINE)),
+            ("error", format!("{} {}", MACRO_PREFIX, NEWLINE)),
+            ("warn", format!("{}1{}", MACRO_PREFIX, NEWLINE)),
+            ("info", format!("{} {}", MACRO_PREFIX, NEWLINE)),
+            (DEBUG_METHOD, format!("{}2{}", MACRO_PREFIX, NEWLINE)),
+        ]);
+    }
+
+    #[test]
+    fn should_end_group_on_drop() {
+        let group = crate::group("parsing");
+        crate::println!("inside");
+        let collapsed = crate::group_collapsed("nested");
+        drop(collapsed);
+        drop(group);
+
+        assert_eq!(take_captured(), [
+            ("group", "parsing".to_owned()),
+            ("info", format!("{}inside{}", MACRO_PREFIX, NEWLINE)),
+            ("groupCollapsed", "nested".to_owned()),
+            ("groupEnd", "".to_owned()),
+            ("groupEnd", "".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_flush_partial_macro_output_on_panic() {
+        struct Panicking;
+
+        impl core::fmt::Display for Panicking {
+            fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                fmt.write_str("partial")?;
+                panic!("display failed");
+            }
+        }
+
+        let result = std::panic::catch_unwind(|| {
+            crate::println!("before {}", Panicking);
+        });
+        assert!(result.is_err());
+
+        assert_eq!(take_captured(), [
+            ("info", format!("{}before partial", MACRO_PREFIX)),
+        ]);
+    }
+
+    #[test]
+    fn should_batch_messages_by_type() {
+        let mut batch = <crate::BatchConsole>::new();
+
+        batch.push(ConsoleType::Info, "1");
+        batch.push_fmt(ConsoleType::Info, format_args!("{}", 2));
+        batch.push(ConsoleType::Error, "3");
+        batch.push(ConsoleType::Info, "4");
+        batch.flush();
+        batch.push(ConsoleType::Info, "5");
+        drop(batch);
+
+        assert_eq!(take_captured(), [
+            ("info", "1\n2".to_owned()),
+            ("error", "3".to_owned()),
+            ("info", "4".to_owned()),
+            ("info", "5".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_count() {
+        crate::count("hits");
+        crate::count_reset("hits");
+
+        assert_eq!(take_captured(), [
+            ("count", "hits".to_owned()),
+            ("countReset", "hits".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_clear_console() {
+        crate::clear();
+
+        assert_eq!(take_captured(), [
+            ("clear", "".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_end_timer_on_drop() {
+        let timer = crate::timer("load");
+        crate::time("parse");
+        crate::time_end("parse");
+        drop(timer);
+
+        assert_eq!(take_captured(), [
+            ("time", "load".to_owned()),
+            ("time", "parse".to_owned()),
+            ("timeEnd", "parse".to_owned()),
+            ("timeEnd", "load".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_end_group_macro_on_drop() {
+        let group = crate::group!("request {}", 1);
+        crate::println!("inside");
+        drop(group);
+
+        assert_eq!(take_captured(), [
+            ("group", "request 1".to_owned()),
+            ("info", format!("{}inside{}", MACRO_PREFIX, NEWLINE)),
+            ("groupEnd", "".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_write_key_values() {
+        let mut evaluated = 0;
+        crate::kv!(ConsoleType::Info, "done");
+        crate::kv!(ConsoleType::Warn, "request", id = { evaluated += 1; 1 }, path = "/", );
+
+        assert_eq!(evaluated, 1);
+        assert_eq!(take_captured(), [
+            ("info", format!("{}done", MACRO_PREFIX)),
+            ("warn", format!("{}request id=1 path=/", MACRO_PREFIX)),
+        ]);
+    }
+
+    #[test]
+    fn should_warn_if_condition_is_false() {
+        let mut evaluated = 0;
+        crate::warn_if!({ evaluated += 1; true }, "{}", DATA);
+        crate::warn_if!({ evaluated += 1; false }, "{}", DATA);
+        assert_eq!(evaluated, 2);
+
+        assert_eq!(take_captured(), [
+            ("warn", format!("{}{}", MACRO_PREFIX, DATA)),
+        ]);
+    }
+
+    #[test]
+    fn should_assert() {
+        let mut evaluated = 0;
+        crate::assert!({ evaluated += 1; true }, "{}", DATA);
+        crate::assert!({ evaluated += 1; false }, "{}", DATA);
+        crate::assert!(1 + 1 == 3);
+        assert_eq!(evaluated, 2);
+
+        let mut formatted = 0;
+        crate::assert!(true, "{}", { formatted += 1; DATA });
+        assert_eq!(formatted, 0);
+
+        assert_eq!(take_captured(), [
+            ("assert", format!("{}{}", MACRO_PREFIX, DATA)),
+            ("assert", format!("{}assertion failed: 1 + 1 == 3", MACRO_PREFIX)),
+        ]);
+    }
+
+    #[cfg(not(feature = "assert-panic"))]
+    #[test]
+    fn should_assert_eq() {
+        let mut evaluated = 0;
+        crate::assert_eq!({ evaluated += 1; 1 }, 1);
+        crate::assert_eq!({ evaluated += 1; 1 }, 2, "{}", DATA);
+        crate::assert_ne!({ evaluated += 1; "1" }, "1");
+        crate::assert_ne!(1, 2, "never");
+        assert_eq!(evaluated, 3);
+
+        assert_eq!(take_captured(), [
+            ("error", format!("{}assertion `left == right` failed: {}\n  left: 1\n right: 2", MACRO_PREFIX, DATA)),
+            ("error", format!("{}assertion `left != right` failed\n  left: \"1\"\n right: \"1\"", MACRO_PREFIX)),
+        ]);
+    }
+
+    #[cfg(feature = "assert-panic")]
+    #[test]
+    #[should_panic(expected = "assertion `left == right` failed")]
+    fn should_panic_on_assert_eq() {
+        crate::assert_eq!(1, 2);
+    }
+
+    #[test]
+    fn should_write_into_borrowed_buffer() {
+        let mut buffer = [0u8; 4];
+        let mut writer = Console::new_with_buffer(ConsoleType::Warn, &mut buffer);
+        assert_eq!(writer.capacity(), 4);
+
+        writer.write_str("123ф");
+        assert_eq!(writer.buffer_str(), "ф");
+        assert_eq!(writer.buffer(), "ф".as_bytes());
+        assert_eq!(writer.write_data(b"4\xff"), 2);
+        assert_eq!(writer.buffer_str(), "\u{FFFD}");
+        writer.write_str("\u{FFFD}");
+        drop(writer);
+
+        let mut tiny = [0u8; 1];
+        let mut writer = crate::BorrowedConsole::new(ConsoleType::Info, &mut tiny);
+        writer.write_str("1ф2");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("warn", "123".to_owned()),
+            ("warn", "ф4".to_owned()),
+            ("warn", "\u{FFFD}".to_owned()),
+            ("warn", "\u{FFFD}".to_owned()),
+            ("info", "1".to_owned()),
+            ("info", "ф".to_owned()),
+            ("info", "2".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_reuse_pooled_console() {
+        static POOL: crate::ConsolePool<2, 16> = crate::ConsolePool::new();
+
+        let mut first = POOL.get(ConsoleType::Info);
+        let mut second = POOL.get(ConsoleType::Warn);
+        let mut fresh = POOL.get(ConsoleType::Error);
+        assert!(first.is_pooled());
+        assert!(second.is_pooled());
+        assert!(!fresh.is_pooled());
+
+        let _ = core::fmt::Write::write_fmt(&mut first, format_args!("{}", 1));
+        second.write_str("2");
+        fresh.write_str("3");
+        drop(second);
+        drop(fresh);
+
+        let mut reused = POOL.get(ConsoleType::Debug);
+        assert!(reused.is_pooled());
+        assert!(reused.is_empty());
+        reused.write_str("4");
+        drop(reused);
+        drop(first);
+
+        assert_eq!(take_captured(), [
+            ("warn", "2".to_owned()),
+            ("error", "3".to_owned()),
+            (DEBUG_METHOD, "4".to_owned()),
+            ("info", "1".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_reset_pooled_console_on_release() {
+        static POOL: crate::ConsolePool<1, 8> = crate::ConsolePool::new();
+
+        let mut writer = POOL.get(ConsoleType::Info);
+        writer.write_str("123456789");
+        #[cfg(feature = "std")]
+        std::io::Write::write_all(&mut *writer, b"A\xe3").unwrap();
+        drop(writer);
+
+        let mut writer = POOL.get(ConsoleType::Warn);
+        assert!(writer.is_pooled());
+        assert_eq!(writer.overflow_count(), 0);
+        #[cfg(feature = "std")]
+        std::io::Write::write_all(&mut *writer, &"ア".as_bytes()[1..]).unwrap_err();
+        writer.write_str("B");
+        drop(writer);
+
+        let first = if cfg!(feature = "std") { "9A\u{FFFD}" } else { "9" };
+        let expected: &[(&str, String)] = if cfg!(feature = "alloc") {
+            &[("info", format!("12345678{}", first)), ("warn", "B".to_owned())]
+        } else {
+            &[("info", "12345678".to_owned()), ("info", first.to_owned()), ("warn", "B".to_owned())]
+        };
+        assert_eq!(take_captured(), expected);
+    }
+
+    #[test]
+    fn should_reuse_shared_writer() {
+        use core::fmt::Write;
+
+        crate::with_writer(ConsoleType::Warn, |writer| {
+            let _ = write!(writer, "{}", DATA);
+            crate::with_writer(ConsoleType::Debug, |nested| {
+                let _ = write!(nested, "nested");
+            });
+            let _ = write!(writer, "{}", DATA);
+        });
+        let is_empty = crate::with_writer(ConsoleType::Info, |writer| {
+            let is_empty = writer.buffer().is_empty();
+            let _ = write!(writer, "2");
+            is_empty
+        });
+        assert!(is_empty);
+
+        assert_eq!(take_captured(), [
+            (DEBUG_METHOD, "nested".to_owned()),
+            ("warn", format!("{}{}", DATA, DATA)),
+            ("info", "2".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_reset_shared_writer_on_release() {
+        crate::with_writer(ConsoleType::Info, |writer| {
+            writer.write_str("C");
+            #[cfg(feature = "std")]
+            std::io::Write::write_all(writer, b"\xe3").unwrap();
+        });
+        crate::with_writer(ConsoleType::Warn, |writer| {
+            assert_eq!(writer.overflow_count(), 0);
+            #[cfg(feature = "std")]
+            std::io::Write::write_all(writer, &"ア".as_bytes()[1..]).unwrap_err();
+            writer.write_str("D");
+        });
+
+        let first = if cfg!(feature = "std") { "C\u{FFFD}" } else { "C" };
+        assert_eq!(take_captured(), [
+            ("info", first.to_owned()),
+            ("warn", "D".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_dbg_expression() {
+        let value = crate::dbg!(1 + 1);
+        assert_eq!(value, 2);
+        let values = crate::dbg!(1, "2");
+        assert_eq!(values, (1, "2"));
+        crate::dbg!();
+
+        let captured = take_captured();
+        assert_eq!(captured.len(), 4);
+        assert!(captured.iter().all(|(method, text)| *method == DEBUG_METHOD && text.starts_with(&format!("{}[src/lib.rs:", MACRO_PREFIX))));
+        assert!(captured[0].1.ends_with("] 1 + 1 = 2"));
+        assert!(captured[1].1.ends_with("] 1 = 1"));
+        assert!(captured[2].1.ends_with("] \"2\" = \"2\""));
+        assert!(captured[3].1.ends_with(']'));
+    }
+
+    #[test]
+    fn should_dispatch_via_console_fn() {
+        (ConsoleType::Error.as_fn())("1");
+        (ConsoleType::Warn.as_fn())("2");
+        (ConsoleType::Info.as_fn())("3");
+        (ConsoleType::Log.as_fn())("4");
+        (ConsoleType::Dir.as_fn())("5");
+        (ConsoleType::Debug.as_fn())("6");
+        (ConsoleType::Trace.as_fn())("7");
+
+        assert_eq!(take_captured(), [
+            ("error", "1".to_owned()),
+            ("warn", "2".to_owned()),
+            ("info", "3".to_owned()),
+            ("log", "4".to_owned()),
+            ("dir", "5".to_owned()),
+            (DEBUG_METHOD, "6".to_owned()),
+            ("trace", "7".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_log_value() {
+        Console::log_value(ConsoleType::Dir, &wasm_bindgen::JsValue::UNDEFINED);
+        Console::log_value(ConsoleType::Error, &wasm_bindgen::JsValue::NULL);
+
+        assert_eq!(take_captured(), [
+            ("dir", "JsValue".to_owned()),
+            ("error", "JsValue".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_log_object() {
+        crate::log_object(ConsoleType::Info, &wasm_bindgen::JsValue::NULL);
+        crate::log_object(ConsoleType::Dir, &wasm_bindgen::JsValue::UNDEFINED);
+
+        assert_eq!(take_captured(), [
+            ("info", "%o".to_owned()),
+            ("dir", "JsValue".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_write_hexdump() {
+        crate::hexdump(ConsoleType::Info, "packet:", b"Hello, world!\x00\x01\x7fabcdef");
+        crate::hexdump(ConsoleType::Warn, "", &[0xff; 16]);
+        crate::hexdump(ConsoleType::Warn, "empty", b"");
+
+        assert_eq!(take_captured(), [
+            ("info", concat!(
+                "packet:\n",
+                "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 00 01 7f  |Hello, world!...|\n",
+                "00000010  61 62 63 64 65 66                                 |abcdef|",
+            ).to_owned()),
+            ("warn", "00000000  ff ff ff ff ff ff ff ff  ff ff ff ff ff ff ff ff  |................|".to_owned()),
+            ("warn", "empty".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_split_hexdump_by_lines() {
+        crate::hexdump(ConsoleType::Info, "", &[0; 16 * 60]);
+
+        let captured = take_captured();
+        assert_eq!(captured.len(), if cfg!(feature = "alloc") { 1 } else { 2 });
+        let lines = captured.iter().flat_map(|(_, text)| text.split('\n')).collect::<Vec<_>>();
+        assert_eq!(lines.len(), 60);
+        assert!(lines.iter().all(|line| line.len() == 78 && line.ends_with("|................|")));
+        assert!(lines[59].starts_with("000003b0  00"));
+    }
+
+    #[test]
+    fn should_log_multiple_values() {
+        use wasm_bindgen::JsValue;
+
+        crate::log_args!(JsValue::NULL);
+        crate::log_args!(JsValue::NULL, JsValue::UNDEFINED);
+        crate::log_args!(JsValue::NULL, JsValue::NULL, JsValue::NULL, JsValue::NULL, JsValue::NULL, JsValue::NULL, JsValue::NULL, JsValue::UNDEFINED,);
+
+        assert_eq!(take_captured(), [
+            ("log", "JsValue".to_owned()),
+            ("log", "2 values".to_owned()),
+            ("log", "8 values".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_display_console_type() {
+        assert_eq!(ConsoleType::default(), ConsoleType::Info);
+        assert_eq!(ConsoleType::Error.to_string(), "error");
+        assert_eq!(ConsoleType::Warn.to_string(), "warn");
+        assert_eq!(ConsoleType::Info.to_string(), "info");
+        assert_eq!(ConsoleType::Debug.to_string(), "debug");
+        assert_eq!(ConsoleType::Log.to_string(), "log");
+        assert_eq!(ConsoleType::Dir.to_string(), "dir");
+        assert_eq!(ConsoleType::Trace.to_string(), "trace");
+    }
+
+    #[test]
+    fn should_parse_console_type() {
+        assert_eq!("error".parse(), Ok(ConsoleType::Error));
+        assert_eq!("WARN".parse(), Ok(ConsoleType::Warn));
+        assert_eq!("Warning".parse(), Ok(ConsoleType::Warn));
+        assert_eq!("info".parse(), Ok(ConsoleType::Info));
+        assert_eq!("dEbUg".parse(), Ok(ConsoleType::Debug));
+        assert_eq!("log".parse(), Ok(ConsoleType::Log));
+        assert_eq!("Dir".parse(), Ok(ConsoleType::Dir));
+        assert_eq!("trace".parse(), Ok(ConsoleType::Trace));
+        assert_eq!("".parse::<ConsoleType>(), Err(ParseConsoleTypeError));
+        assert_eq!("inf".parse::<ConsoleType>(), Err(ParseConsoleTypeError));
+        assert_eq!("unknown".parse::<ConsoleType>().unwrap_or_default(), ConsoleType::Info);
+    }
+
+    #[test]
+    fn should_round_trip_name() {
+        use core::convert::TryFrom;
+
+        for level in 0..=6 {
+            let typ = ConsoleType::try_from(level).unwrap();
+            let name: &'static str = typ.into();
+            assert_eq!(name, typ.as_str());
+            assert_eq!(name, typ.to_string());
+            assert_eq!(name.parse(), Ok(typ));
+        }
+    }
+
+    #[cfg(feature = "timestamp")]
+    #[test]
+    fn should_prefix_timestamp() {
+        let mut writer = Console::with_timestamp(ConsoleType::Info);
+
+        assert!(writer.buffer().is_empty());
+        writer.write_data(DATA.as_bytes());
+        writer.write_data(DATA.as_bytes());
+        assert_eq!(writer.buffer(), format!("[1.500] {}{}", DATA, DATA).as_bytes());
+        writer.flush();
+        writer.write_data(b"2");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", format!("[1.500] {}{}", DATA, DATA)),
+            ("info", "[1.500] 2".to_owned()),
+        ]);
+    }
+
+    #[cfg(feature = "timestamp")]
+    #[test]
+    fn should_limit_rate_of_messages() {
+        let mut writer = Console::with_rate_limit(ConsoleType::Info, 2);
+
+        for idx in 1..=4 {
+            writer.write_str(&idx.to_string());
+            writer.flush();
+        }
+        super::tests::NOW.with(|now| now.set(1001.5));
+        for idx in 5..=7 {
+            writer.write_str(&idx.to_string());
+            writer.flush();
+        }
+        drop(writer);
+        super::tests::NOW.with(|now| now.set(1.5));
+
+        assert_eq!(take_captured(), [
+            ("info", "1".to_owned()),
+            ("info", "2".to_owned()),
+            ("info", "… 2 messages suppressed".to_owned()),
+            ("info", "5".to_owned()),
+            ("info", "6".to_owned()),
+            ("info", "… 1 messages suppressed".to_owned()),
+        ]);
+    }
+
+    #[cfg(all(feature = "timestamp", not(feature = "alloc")))]
+    #[test]
+    fn should_make_progress_when_timestamp_fills_buffer() {
+        let mut writer = Console::<4>::sized(ConsoleType::Info).timestamped();
+
+        assert_eq!(writer.write_data(b"12"), 2);
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "[1.5".to_owned()),
+            ("info", "1".to_owned()),
+            ("info", "[1.5".to_owned()),
+            ("info", "2".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_create_with_type_shortcut() {
+        assert_eq!(Console::error().typ, ConsoleType::Error);
+        assert_eq!(Console::warn().typ, ConsoleType::Warn);
+        assert_eq!(Console::info().typ, ConsoleType::Info);
+        assert_eq!(Console::log().typ, ConsoleType::Log);
+        assert_eq!(Console::dir().typ, ConsoleType::Dir);
+        assert_eq!(Console::debug().typ, ConsoleType::Debug);
+        assert_eq!(Console::trace().typ, ConsoleType::Trace);
+    }
+
+    #[test]
+    fn should_flush_before_type_change() {
+        let mut writer = Console::new(ConsoleType::Error);
+
+        writer.write_data(b"1");
+        writer.set_type(ConsoleType::Warn);
+        assert!(writer.buffer().is_empty());
+        writer.write_data(b"2");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("error", "1".to_owned()),
+            ("warn", "2".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_truncate_to_max_len() {
+        let mut writer = Console::with_max_len(ConsoleType::Info, 4);
+
+        writer.write_str("12");
+        writer.write_str("Ф");
+        writer.flush();
+        writer.write_str("123Ф");
+        writer.write_str("discarded");
+        writer.flush();
+        writer.write_str("1234");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "12Ф".to_owned()),
+            ("info", "123…".to_owned()),
+            ("info", "1234".to_owned()),
+        ]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_truncate_message_split_by_overflow() {
+        let mut writer = Console::<4>::sized(ConsoleType::Info).max_len(6);
+
+        assert_eq!(writer.write_data(b"123456789"), 9);
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "1234".to_owned()),
+            ("info", "56".to_owned()),
+            ("info", "…".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_flush_each_line_when_streaming() {
+        let mut writer = Console::streaming(ConsoleType::Info);
+
+        writer.write_str("1\n2\n\n3");
+        assert_eq!(writer.buffer_str(), "3");
+        writer.write_str("4");
+        writer.write_str("\n5");
+        assert_eq!(writer.buffer_str(), "5");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "1\n".to_owned()),
+            ("info", "2\n".to_owned()),
+            ("info", "\n".to_owned()),
+            ("info", "34\n".to_owned()),
+            ("info", "5".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_write_into_own_sink() {
+        use super::Sink;
+        use std::sync::Mutex;
+
+        struct VecSink(Mutex<Vec<(ConsoleType, String)>>);
+
+        impl Sink for VecSink {
+            fn write(&self, typ: ConsoleType, text: &str) {
+                self.0.lock().unwrap().push((typ, text.to_owned()));
+            }
+        }
+
+        static SINK: VecSink = VecSink(Mutex::new(Vec::new()));
+
+        let mut writer = Console::with_sink(ConsoleType::Warn, &SINK);
+        writer.write_str(DATA);
+        writer.set_type(ConsoleType::Debug);
+        writer.write_str("1");
+        drop(writer);
+
+        assert!(take_captured().is_empty());
+        assert_eq!(*SINK.0.lock().unwrap(), [
+            (ConsoleType::Warn, DATA.to_owned()),
+            (ConsoleType::Debug, "1".to_owned()),
+        ]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_try_write_data_within_capacity() {
+        use super::CapacityError;
+
+        let mut writer = Console::<8>::sized(ConsoleType::Info).prefixed("> ");
+
+        assert_eq!(writer.try_write_data(b"1234567"), Err(CapacityError {
+            needed: 9,
+            available: 8,
+        }));
+        assert!(writer.is_empty());
+        assert_eq!(writer.try_write_data(b"12\xFF"), Ok(()));
+        assert_eq!(writer.buffer_str(), "> 12\u{FFFD}");
+        assert_eq!(writer.try_write_data(b"\xFF"), Err(CapacityError {
+            needed: 3,
+            available: 1,
+        }));
+        assert_eq!(writer.buffer_str(), "> 12\u{FFFD}");
+        assert_eq!(writer.try_write_data(b"3"), Ok(()));
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "> 12\u{FFFD}3".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_write_char() {
+        use core::fmt::Write;
+
+        let mut writer = Console::<4>::sized(ConsoleType::Info).prefixed(">");
+        for ch in ['1', '2', 'Ф', '3', '\u{1F600}'].iter() {
+            let _ = writer.write_char(*ch);
+        }
+        drop(writer);
+
+        let expected: &[(&str, String)] = if cfg!(feature = "alloc") {
+            &[("info", ">12Ф3\u{1F600}".to_owned())]
+        } else {
+            &[
+                ("info", ">12".to_owned()),
+                ("info", ">Ф3".to_owned()),
+                ("info", ">".to_owned()),
+                ("info", "\u{1F600}".to_owned()),
+            ]
+        };
+        assert_eq!(take_captured(), expected);
+    }
+
+    #[test]
+    fn should_not_output_empty_write() {
+        let mut writer = Console::new(ConsoleType::Info);
+        writer.write_str("");
+        assert!(writer.is_empty());
+        drop(writer);
+
+        assert!(take_captured().is_empty());
+    }
+
+    #[test]
+    fn should_write_into_spare_capacity() {
+        let mut writer = Console::with_prefix(ConsoleType::Info, "> ");
+        let data = "1Ф".as_bytes();
+
+        let spare = writer.spare_capacity_mut();
+        for (dest, byte) in spare.iter_mut().zip(data.iter()) {
+            *dest = core::mem::MaybeUninit::new(*byte);
+        }
+        unsafe {
+            writer.advance(data.len());
+        }
+        writer.write_str("2");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "> 1Ф2".to_owned()),
+        ]);
+
+        //Accessor alone doesn't start new message.
+        let mut writer = Console::with_prefix(ConsoleType::Info, "> ");
+        assert!(!writer.spare_capacity_mut().is_empty());
+        assert!(writer.is_empty());
+        unsafe {
+            writer.advance(0);
+        }
+        assert!(writer.is_empty());
+        drop(writer);
+
+        assert!(take_captured().is_empty());
+    }
+
+    #[test]
+    fn should_write_line_with_content() {
+        let mut writer = Console::<8>::sized(ConsoleType::Info);
+
+        writer.write_line(b"1234567");
+        writer.flush();
+        writer.write_line(b"12345678");
+        writer.flush();
+        writer.write_line("123456Ф".as_bytes());
+        drop(writer);
+
+        let expected: &[(&str, String)] = if cfg!(feature = "alloc") {
+            &[
+                ("info", "1234567\n".to_owned()),
+                ("info", "12345678\n".to_owned()),
+                ("info", "123456Ф\n".to_owned()),
+            ]
+        } else {
+            &[
+                ("info", "1234567\n".to_owned()),
+                ("info", "1234567".to_owned()),
+                ("info", "8\n".to_owned()),
+                ("info", "123456".to_owned()),
+                ("info", "Ф\n".to_owned()),
+            ]
+        };
+        assert_eq!(take_captured(), expected);
+    }
+
+    #[test]
+    fn should_write_saturated_line() {
+        let mut writer = Console::<4>::sized(ConsoleType::Info).saturated();
+
+        writer.write_line(b"123456");
+        assert_eq!(writer.was_truncated(), !cfg!(feature = "alloc"));
+        drop(writer);
+
+        let expected = if cfg!(feature = "alloc") { "123456\n" } else { "1234" };
+        assert_eq!(take_captured(), [("info", expected.to_owned())]);
+    }
+
+    #[test]
+    fn should_write_line_within_max_len() {
+        let mut writer = Console::<4>::sized(ConsoleType::Info).max_len(5);
+
+        writer.write_line(b"1234");
+        drop(writer);
+
+        let expected: &[(&str, String)] = if cfg!(feature = "alloc") {
+            &[("info", "1234\n".to_owned())]
+        } else {
+            &[
+                ("info", "123".to_owned()),
+                ("info", "4\n".to_owned()),
+            ]
+        };
+        assert_eq!(take_captured(), expected);
+    }
+
+    #[test]
+    fn should_return_buffer_as_str() {
+        let mut writer = Console::new(ConsoleType::Info);
+
+        assert_eq!(writer.buffer_str(), "");
+        writer.write_data(b"1\xF0\x9F");
+        assert_eq!(writer.buffer_str(), "1\u{FFFD}");
+        writer.clear();
+    }
+
+    #[test]
+    fn should_write_via_borrowed_writer() {
+        use core::fmt::Write;
+
+        fn write_value(mut writer: impl Write, value: u32) {
+            let _ = write!(writer, "value={}", value);
+        }
+
+        let mut writer = Console::new(ConsoleType::Info);
+        write_value(writer.writer(), 1);
+        let _ = write!(writer.writer(), ";");
+        write_value(writer.writer(), 2);
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "value=1;value=2".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_write_fmt_chained() {
+        Console::info().write_fmt_chained(format_args!("{}", 1)).write_fmt_chained(format_args!("{}", 2));
+
+        assert_eq!(take_captured(), [
+            ("info", "12".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_edit_buffer_in_place() {
+        let mut writer = Console::new(ConsoleType::Info);
+
+        writer.write_str("token=secret");
+        for byte in unsafe { &mut writer.buffer_mut()[6..] } {
+            *byte = b'*';
+        }
+        writer.flush();
+        writer.write_str("upper");
+        writer.buffer_str_mut().make_ascii_uppercase();
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "token=******".to_owned()),
+            ("info", "UPPER".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_drain_to_callback() {
+        let mut drained = Vec::new();
+        let mut writer = Console::new(ConsoleType::Warn);
+
+        writer.drain_to(|typ, text| drained.push((typ, text.to_owned())));
+        writer.write_str(DATA);
+        writer.drain_to(|typ, text| drained.push((typ, text.to_owned())));
+        assert!(writer.is_empty());
+        drop(writer);
+
+        assert!(take_captured().is_empty());
+        assert_eq!(drained, [
+            (ConsoleType::Warn, DATA.to_owned()),
+        ]);
+    }
+
+    #[cfg(feature = "bench")]
+    #[test]
+    fn should_capture_into_test_sink() {
+        static SINK: crate::TestSink = crate::TestSink::new();
+
+        let mut writer = Console::<4>::sized(ConsoleType::Info).sink(&SINK);
+        writer.write_str("123456");
+        let overflow_count = writer.force_flush_count();
+        assert_eq!(overflow_count, writer.overflow_count());
+        drop(writer);
+
+        if cfg!(feature = "alloc") {
+            assert_eq!(overflow_count, 0);
+            assert_eq!(SINK.take(), [(ConsoleType::Info, "123456".to_owned())]);
+        } else {
+            assert_eq!(overflow_count, 1);
+            assert_eq!(SINK.take(), [
+                (ConsoleType::Info, "1234".to_owned()),
+                (ConsoleType::Info, "56".to_owned()),
+            ]);
+        }
+        assert!(SINK.take().is_empty());
+        assert!(take_captured().is_empty());
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn should_capture_last_flushed_messages() {
+        use core::fmt::Write;
+
+        assert_eq!(Console::peek_last_flush(), None);
+
+        for idx in 0..=crate::CAPTURED_CAPACITY {
+            let mut writer = Console::new(ConsoleType::Warn);
+            let _ = write!(writer, "{}", idx);
+        }
+        let mut writer = Console::styled(ConsoleType::Error, "color: red");
+        writer.write_str("last");
+        drop(writer);
+
+        let captured = crate::captured();
+        assert_eq!(captured.len(), crate::CAPTURED_CAPACITY);
+        assert_eq!(captured[0], (ConsoleType::Warn, "2".to_owned()));
+        assert_eq!(captured[crate::CAPTURED_CAPACITY - 1], (ConsoleType::Error, "last".to_owned()));
+        assert_eq!(Console::peek_last_flush().as_deref(), Some("last"));
+        take_captured();
+    }
+
+    #[test]
+    fn should_normalize_newlines() {
+        let mut writer = Console::normalize_newlines(ConsoleType::Info);
+        assert_eq!(writer.write_text("1\r\n2\r3\n\r"), 8);
+        assert_eq!(writer.buffer_str(), "1\n2\n3\n\n");
+
+        //`\r\n` split across writes
+        assert_eq!(writer.write_text("4\r"), 2);
+        assert_eq!(writer.write_text("\n5"), 2);
+        assert_eq!(writer.buffer_str(), "1\n2\n3\n\n4\n5");
+        writer.write_str("\r");
+        writer.write_str("\r");
+        writer.write_str("\n");
+        assert_eq!(writer.buffer_str(), "1\n2\n3\n\n4\n5\n\n");
+        writer.flush();
+
+        //New message doesn't continue previous one.
+        writer.write_str("6\r");
+        writer.flush();
+        writer.write_str("\n7");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "1\n2\n3\n\n4\n5\n\n".to_owned()),
+            ("info", "6\n".to_owned()),
+            ("info", "\n7".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_append_newline() {
+        let mut writer = Console::<4>::sized(ConsoleType::Info).newline_terminated();
+        writer.flush();
+        assert!(take_captured().is_empty());
+
+        writer.write_str("1\n");
+        writer.flush();
+        writer.write_str("123");
+        #[cfg(not(feature = "alloc"))]
+        assert_eq!(writer.remaining(), 0);
+        writer.flush();
+        writer.write_str("\n");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "1\n".to_owned()),
+            ("info", "123\n".to_owned()),
+            ("info", "\n".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_merge_other_buffer() {
+        let mut writer = Console::<4>::sized(ConsoleType::Info);
+        let mut other = Console::with_prefix(ConsoleType::Error, "-");
+        writer.write_str("12");
+        other.write_str("345");
+
+        writer.merge(&other);
+        assert_eq!(other.buffer_str(), "-345");
+        other.clear();
+        drop(writer);
+
+        if cfg!(feature = "alloc") {
+            assert_eq!(take_captured(), [("info", "12-345".to_owned())]);
+        } else {
+            assert_eq!(take_captured(), [
+                ("info", "12-3".to_owned()),
+                ("info", "45".to_owned()),
+            ]);
+        }
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_saturate_on_overflow() {
+        let mut writer = Console::<4>::sized(ConsoleType::Info).saturated();
+        assert_eq!(writer.write_data(b"1234"), 4);
+        assert!(!writer.was_truncated());
+        writer.flush();
+
+        assert_eq!(writer.write_data(b"12345"), 5);
+        assert!(writer.was_truncated());
+        assert_eq!(writer.write_data(b"6"), 1);
+        assert_eq!(writer.buffer_str(), "1234");
+        writer.flush();
+        assert!(!writer.was_truncated());
+
+        assert_eq!(writer.write_data("123ф".as_bytes()), 5);
+        assert!(writer.was_truncated());
+        assert_eq!(writer.write_data(b"4"), 1);
+        assert_eq!(writer.buffer_str(), "123");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "1234".to_owned()),
+            ("info", "1234".to_owned()),
+            ("info", "123".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_write_display_and_debug() {
+        let mut writer = Console::new(ConsoleType::Info);
+        writer.write_display(&1).write_display(" ").write_debug("2").write_debug(&Some(3));
+        drop(writer);
+
+        assert_eq!(take_captured(), [("info", "1 \"2\"Some(3)".to_owned())]);
+    }
+
+    #[cfg(feature = "colored")]
+    #[test]
+    fn should_set_style_of_type() {
+        assert_eq!(crate::style(ConsoleType::Error), "color: red");
+        assert_eq!(crate::style(ConsoleType::Log), "");
+
+        crate::set_style(ConsoleType::Trace, "color: green");
+        assert_eq!(crate::style(ConsoleType::Trace), "color: green");
+
+        //Palette is not applied by test backend.
+        let mut writer = Console::new(ConsoleType::Trace);
+        writer.write_str("1");
+        drop(writer);
+        assert_eq!(take_captured(), [("trace", "1".to_owned())]);
+    }
+
+    #[cfg(all(feature = "colored", not(feature = "node")))]
+    #[test]
+    fn should_apply_palette_only_to_styled_output() {
+        super::tests::PALETTE.with(|palette| palette.set(true));
+
+        let mut writer = Console::new(ConsoleType::Error);
+        writer.write_str("x");
+        assert_eq!(writer.buffer_str(), "%cx");
+        drop(writer);
+
+        crate::assert_args(false, format_args!("y"));
+        drop(crate::group_args(format_args!("z")));
+
+        #[cfg(feature = "timestamp")]
+        {
+            let mut writer = Console::with_rate_limit(ConsoleType::Info, 1);
+            writer.write_str("1");
+            writer.flush();
+            writer.write_str("2");
+            drop(writer);
+        }
+        super::tests::PALETTE.with(|palette| palette.set(false));
+
+        let mut expected = vec![
+            ("error", "%cx".to_owned()),
+            ("assert", "y".to_owned()),
+            ("group", "z".to_owned()),
+            ("groupEnd", "".to_owned()),
+        ];
+        if cfg!(feature = "timestamp") {
+            expected.push(("info", "%c1".to_owned()));
+            expected.push(("info", "%c… 1 messages suppressed".to_owned()));
+        }
+        assert_eq!(take_captured(), expected);
+    }
+
+    #[test]
+    fn should_apply_modes_to_written_chars() {
+        use core::fmt::Write;
+
+        let mut writer = Console::with_max_len(ConsoleType::Info, 2);
+        for ch in ['a', 'b', 'c', 'd'] {
+            let _ = writer.write_char(ch);
+        }
+        drop(writer);
+
+        let mut writer = Console::streaming(ConsoleType::Info);
+        for ch in ['a', '\n', 'b'] {
+            let _ = writer.write_char(ch);
+        }
+        assert_eq!(writer.buffer_str(), "b");
+        drop(writer);
+
+        let mut writer = Console::normalize_newlines(ConsoleType::Info);
+        for ch in ['a', '\r', 'b'] {
+            let _ = writer.write_char(ch);
+        }
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "ab…".to_owned()),
+            ("info", "a\n".to_owned()),
+            ("info", "b".to_owned()),
+            ("info", "a\nb".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_start_new_message_after_clear_or_take() {
+        let mut writer = Console::with_max_len(ConsoleType::Info, 3);
+        writer.write_str("12345");
+        writer.clear();
+        writer.write_str("ab");
+        writer.flush();
+
+        writer.write_str("12345");
+        assert_eq!(writer.take(), "123");
+        writer.write_str("cd");
+        writer.flush();
+
+        writer.write_str("12345");
+        writer.drain_to(|_, text| assert_eq!(text, "123"));
+        writer.write_str("ef");
+        drop(writer);
+
+        #[cfg(not(feature = "alloc"))]
+        {
+            let mut writer = Console::<4>::sized(ConsoleType::Warn).saturated();
+            writer.write_str("12345");
+            assert!(writer.was_truncated());
+            writer.take();
+            assert!(!writer.was_truncated());
+        }
+
+        let mut writer = Console::normalize_newlines(ConsoleType::Warn);
+        writer.write_str("1\r");
+        writer.clear();
+        writer.write_str("\n2");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "ab".to_owned()),
+            ("info", "cd".to_owned()),
+            ("info", "ef".to_owned()),
+            ("warn", "\n2".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_stream_lines_written_via_try_write_data() {
+        let mut writer = Console::streaming(ConsoleType::Info);
+
+        assert_eq!(writer.try_write_data(b"1\n2"), Ok(()));
+        assert_eq!(writer.buffer_str(), "2");
+        assert_eq!(take_captured(), [("info", "1\n".to_owned())]);
+        writer.clear();
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_not_write_data_into_reserved_capacity() {
+        use crate::CapacityError;
+
+        let mut writer = Console::<16>::sized(ConsoleType::Info).numbered();
+        assert_eq!(writer.try_write_data(b"1234567890"), Err(CapacityError {
+            needed: 10,
+            available: 4,
+        }));
+        assert!(writer.is_empty());
+
+        let mut writer = Console::<4>::sized(ConsoleType::Info).newline_terminated();
+        assert_eq!(writer.try_write_data(b"1234"), Err(CapacityError {
+            needed: 4,
+            available: 3,
+        }));
+        assert_eq!(writer.try_write_data(b"123"), Ok(()));
+        drop(writer);
+
+        assert_eq!(take_captured(), [("info", "123\n".to_owned())]);
+    }
+
+    #[test]
+    fn should_write_args() {
+        let value = 1;
+        let mut writer = Console::new(ConsoleType::Info);
+        writer.write_args(format_args!("{}-{}", value, DATA));
+        writer.write_str("!");
+        drop(writer);
+
+        assert_eq!(take_captured(), [("info", format!("1-{}!", DATA))]);
+    }
+
+    #[test]
+    fn should_flush_if_full() {
+        let mut writer = Console::<4>::sized(ConsoleType::Info);
+        writer.write_str("123");
+        writer.flush_if_full();
+        assert_eq!(writer.len(), 3);
+
+        writer.write_str("4");
+        writer.flush_if_full();
+        if cfg!(feature = "alloc") {
+            assert_eq!(writer.len(), 4);
+            writer.flush();
+        } else {
+            assert!(writer.is_empty());
+        }
+
+        assert_eq!(take_captured(), [("info", "1234".to_owned())]);
+    }
+
+    #[test]
+    fn should_copy_utf8_truncated() {
+        let text = "1тест".as_bytes();
+        let mut out = [0u8; 16];
+
+        assert_eq!(crate::copy_utf8_truncated(&mut out, text), 9);
+        assert_eq!(&out[..9], text);
+        assert_eq!(crate::copy_utf8_truncated(&mut out[..2], text), 1);
+        assert_eq!(crate::copy_utf8_truncated(&mut out[..3], text), 3);
+        assert_eq!(crate::copy_utf8_truncated(&mut out[..4], text), 3);
+        assert_eq!(crate::copy_utf8_truncated(&mut out[..0], text), 0);
+        assert_eq!(crate::copy_utf8_truncated(&mut out, b""), 0);
+        assert_eq!(&out[..3], "1т".as_bytes());
+    }
+
+    #[test]
+    fn should_copy_out_buffered_text() {
+        let mut writer = Console::new(ConsoleType::Info);
+        writer.write_str("1тест");
+
+        let mut out = [0u8; 16];
+        assert_eq!(writer.copy_out(&mut out), 9);
+        assert_eq!(&out[..9], "1тест".as_bytes());
+
+        let mut out = [0u8; 4];
+        assert_eq!(writer.copy_out(&mut out), 3);
+        assert_eq!(&out[..3], "1т".as_bytes());
+
+        assert_eq!(writer.buffer_str(), "1тест");
+        writer.clear();
+        assert!(take_captured().is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn should_return_inner_buffer_without_flush() {
+        let mut writer = Console::with_max_len(ConsoleType::Info, 2);
+        writer.write_str("123");
+
+        assert_eq!(writer.into_inner(), b"12");
+        assert!(take_captured().is_empty());
+    }
+
+    #[test]
+    fn should_prefix_sequence_number() {
+        let mut first = Console::<20>::sized(ConsoleType::Info).numbered();
+        let mut second = Console::with_seq(ConsoleType::Warn);
+        first.write_str("first");
+        second.write_str("second");
+        second.flush();
+        #[cfg(not(feature = "alloc"))]
+        assert_eq!(first.remaining(), 20 - 12 - 5);
+        first.write_str("1234");
+        drop(first);
+        second.flush();
+        second.write_str("third");
+        drop(second);
+
+        if cfg!(feature = "alloc") {
+            assert_eq!(take_captured(), [
+                ("warn", "#0 second".to_owned()),
+                ("info", "#1 first1234".to_owned()),
+                ("warn", "#2 third".to_owned()),
+            ]);
+        } else {
+            assert_eq!(take_captured(), [
+                ("warn", "#0 second".to_owned()),
+                ("info", "#1 first123".to_owned()),
+                ("info", "#2 4".to_owned()),
+                ("warn", "#3 third".to_owned()),
+            ]);
+        }
+
+        //Number is assigned per flush, hence only the first line carries it.
+        //Kept in the same test, as numbers are global.
+        let mut writer = Console::<32>::sized(ConsoleType::Info).numbered().split_lines();
+        writer.write_str("1\n2\n");
+        drop(writer);
+
+        let seq = if cfg!(feature = "alloc") { 3 } else { 4 };
+        assert_eq!(take_captured(), [
+            ("info", format!("#{} 1", seq)),
+            ("info", "2".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_take_buffered_text() {
+        let mut writer = Console::with_prefix(ConsoleType::Info, "> ");
+
+        writer.write_str(DATA);
+        assert_eq!(writer.take(), format!("> {}", DATA));
+        assert!(writer.is_empty());
+        assert_eq!(writer.take(), "");
+        writer.write_str("1");
+        assert_eq!(writer.take(), "> 1");
+        drop(writer);
+
+        assert!(take_captured().is_empty());
+    }
+
+    #[test]
+    fn should_reset_to_new_message() {
+        let mut writer = Console::with_prefix(ConsoleType::Error, "> ");
+
+        writer.write_str("1");
+        writer.reset(ConsoleType::Info);
+        assert!(writer.is_empty());
+        writer.write_str("2");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("error", "> 1".to_owned()),
+            ("info", "> 2".to_owned()),
+        ]);
+
+        let mut writer = Console::<4>::sized(ConsoleType::Warn);
+        writer.write_str("12345");
+        assert_eq!(writer.overflow_count(), if cfg!(feature = "alloc") { 0 } else { 1 });
+        writer.reset(ConsoleType::Info);
+        assert_eq!(writer.overflow_count(), 0);
+        drop(writer);
+
+        let expected: &[(&str, String)] = if cfg!(feature = "alloc") {
+            &[("warn", "12345".to_owned())]
+        } else {
+            &[
+                ("warn", "1234".to_owned()),
+                ("warn", "5".to_owned()),
+            ]
+        };
+        assert_eq!(take_captured(), expected);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_reset_incomplete_io_sequence() {
+        use std::io::Write;
+
+        let mut writer = Console::new(ConsoleType::Warn);
+        writer.write_all(b"1\xe3").unwrap();
+        writer.reset(ConsoleType::Info);
+        writer.write_all(b"2").unwrap();
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("warn", "1\u{FFFD}".to_owned()),
+            ("info", "2".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_prefix_style_directive() {
+        let mut writer = Console::styled(ConsoleType::Warn, "color: red");
+
+        writer.write_data(DATA.as_bytes());
+        assert_eq!(writer.buffer(), format!("%c{}", DATA).as_bytes());
+        writer.flush();
+        writer.write_data(b"2");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("warn", format!("%c{}", DATA)),
+            ("warn", "%c2".to_owned()),
+        ]);
+
+        let mut writer = Console::styled(ConsoleType::Dir, "color: red");
+        writer.write_data(DATA.as_bytes());
+        drop(writer);
+        assert_eq!(take_captured(), [("dir", DATA.to_owned())]);
+    }
+
+    #[cfg(feature = "panic")]
+    #[test]
+    fn should_write_panic_via_hook() {
+        let default_hook = std::panic::take_hook();
+        crate::init_panic_hook();
+        let result = std::panic::catch_unwind(|| panic!("boom"));
+        std::panic::set_hook(default_hook);
+        assert!(result.is_err());
+
+        let captured = take_captured();
+        assert_eq!(captured.len(), 1);
+        assert_eq!(captured[0].0, "error");
+        assert!(captured[0].1.contains("boom"));
+
+        let default_hook = std::panic::take_hook();
+        crate::init_panic_hook();
+        let result = std::panic::catch_unwind(|| panic!("{}", "ロ".repeat(super::BUFFER_CAPACITY)));
+        std::panic::set_hook(default_hook);
+        assert!(result.is_err());
+
+        let captured = take_captured();
+        assert_eq!(captured.len(), 1);
+        assert!(captured[0].1.len() <= super::BUFFER_CAPACITY);
+        assert!(captured[0].1.ends_with('ロ'));
+    }
+
+    #[test]
+    fn should_write_str_without_trait() {
+        let mut writer = Console::new(ConsoleType::Warn);
+
+        writer.write_str(DATA);
+        unsafe {
+            writer.write_utf8_unchecked("ロリ".as_bytes());
+        }
+        assert_eq!(writer.buffer(), format!("{}ロリ", DATA).as_bytes());
+    }
+
+    #[test]
+    fn should_convert_to_and_from_u8() {
+        use core::convert::TryFrom;
+
+        const TYPES: [ConsoleType; 7] = [
+            ConsoleType::Error,
+            ConsoleType::Warn,
+            ConsoleType::Info,
+            ConsoleType::Log,
+            ConsoleType::Dir,
+            ConsoleType::Debug,
+            ConsoleType::Trace,
+        ];
+
+        for (idx, typ) in TYPES.iter().enumerate() {
+            assert_eq!(u8::from(*typ), idx as u8);
+            assert_eq!(ConsoleType::try_from(idx as u8), Ok(*typ));
+        }
+        assert_eq!(ConsoleType::try_from(7), Err(ParseConsoleTypeError));
+    }
+
+    #[test]
+    fn should_order_by_verbosity() {
+        assert!(ConsoleType::Error < ConsoleType::Warn);
+        assert!(ConsoleType::Warn < ConsoleType::Info);
+        assert!(ConsoleType::Info < ConsoleType::Log);
+        assert!(ConsoleType::Log < ConsoleType::Dir);
+        assert!(ConsoleType::Dir < ConsoleType::Debug);
+        assert!(ConsoleType::Debug < ConsoleType::Trace);
+        assert_eq!(ConsoleType::Trace.max(ConsoleType::Error), ConsoleType::Trace);
+    }
+
+    #[test]
+    fn should_create_with_checked_capacity() {
+        const WRITER: Console<4> = Console::new_const_checked(ConsoleType::Info);
+
+        let mut writer = WRITER;
+        writer.write_str("1");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "1".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_report_backend() {
+        let expected = if cfg!(feature = "disable") {
+            "disabled"
+        } else if cfg!(feature = "node") {
+            "node"
+        } else if cfg!(feature = "custom-namespace") {
+            "webLog"
+        } else {
+            "console"
+        };
+        assert_eq!(crate::backend(), expected);
+    }
+
+    #[test]
+    fn should_expose_capacity() {
+        assert_eq!(<Console>::CAPACITY, super::BUFFER_CAPACITY);
+        assert_eq!(Console::<8>::CAPACITY, 8);
+    }
+
+    #[test]
+    fn should_infer_default_capacity() {
+        fn capacity_of<const N: usize>(_: &Console<N>) -> usize {
+            N
+        }
+
+        assert_eq!(capacity_of(&Console::error()), super::BUFFER_CAPACITY);
+        assert_eq!(capacity_of(&Console::with_prefix(ConsoleType::Info, "> ")), super::BUFFER_CAPACITY);
+
+        let mut writer = Console::<8>::sized(ConsoleType::Info).prefixed("> ").max_len(2);
+        assert_eq!(capacity_of(&writer), 8);
+        writer.write_str("123");
+        writer.flush();
+        assert_eq!(take_captured(), [("info", "> 12…".to_owned())]);
+    }
+
+    #[test]
+    fn should_be_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Console>();
+        assert_send_sync::<Console<1>>();
+    }
+
+    #[test]
+    fn should_display_buffered_text() {
+        let mut writer = Console::with_prefix(ConsoleType::Info, "> ");
+
+        assert_eq!(format!("{}", writer), "");
+        writer.write_str(DATA);
+        assert_eq!(format!("[{}]", writer), format!("[> {}]", DATA));
+        assert_eq!(writer.len(), DATA.len() + 2);
+        writer.clear();
+    }
+
+    #[test]
+    fn should_debug_format_buffered_preview() {
+        let mut writer = Console::<128>::sized(ConsoleType::Warn);
+
+        writer.write_data(b"1");
+        writer.write_data("ロ".repeat(30).as_bytes());
+        let expected = format!("Console {{ typ: Warn, len: 91, capacity: {}, preview: \"1{}\" }}", writer.capacity(), "ロ".repeat(21));
+        assert_eq!(format!("{:?}", writer), expected);
+        writer.clear();
+    }
+
+    #[test]
+    fn should_write_prefix_on_each_message() {
+        let mut writer = Console::with_prefix(ConsoleType::Info, "[render] ");
+
+        writer.flush();
+        writer.write_data(DATA.as_bytes());
+        writer.write_data(DATA.as_bytes());
+        writer.flush();
+        writer.write_data(b"2");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", format!("[render] {}{}", DATA, DATA)),
+            ("info", "[render] 2".to_owned()),
+        ]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_write_prefix_after_overflow() {
+        let mut writer = Console::<12>::sized(ConsoleType::Info).prefixed("[ロ] ");
+
+        writer.write_data(DATA.as_bytes());
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "[ロ] 123456".to_owned()),
+            ("info", "[ロ] 7891".to_owned()),
+        ]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_truncate_prefix_exceeding_capacity() {
+        let mut writer = Console::<4>::sized(ConsoleType::Info).prefixed("12ロ");
+
+        assert_eq!(writer.write_data(b"3"), 1);
+        assert_eq!(writer.buffer(), b"123");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "123".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_trim_trailing_newline() {
+        use core::fmt::Write;
+
+        let mut writer = Console::new_trimmed(ConsoleType::Info);
+
+        let _ = writeln!(writer, "{}", DATA);
+        writer.flush();
+        let _ = write!(writer, "{}\r\n", DATA);
+        writer.flush();
+        let _ = write!(writer, "1\n\n");
+        writer.flush();
+        let _ = write!(writer, "\n2");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", DATA.to_owned()),
+            ("info", DATA.to_owned()),
+            ("info", "1\n".to_owned()),
+            ("info", "\n2".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_split_lines() {
+        let mut writer = Console::line_split(ConsoleType::Info);
+        writer.write_str("1\n\n2\n3");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "1".to_owned()),
+            ("info", "".to_owned()),
+            ("info", "2".to_owned()),
+            ("info", "3".to_owned()),
+        ]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn should_split_lines_on_overflow() {
+        let mut writer = Console::<8>::sized(ConsoleType::Info).split_lines();
+        writer.write_str("12\n3456789");
+        assert_eq!(writer.buffer(), b"3456789");
+        writer.write_str("abcdefghij\n");
+        drop(writer);
+
+        assert_eq!(take_captured(), [
+            ("info", "12".to_owned()),
+            ("info", "3456789a".to_owned()),
+            ("info", "bcdefghi".to_owned()),
+            ("info", "j".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn should_replace_invalid_utf8() {
+        let mut writer = Console::new(ConsoleType::Warn);
+
+        assert_eq!(writer.write_data(b"1\xff2\xe3\x83"), 5);
+        assert_eq!(writer.buffer(), "1\u{FFFD}2\u{FFFD}".as_bytes());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_reject_invalid_utf8_via_io() {
+        use std::io::Write;
+
+        let mut writer = Console::new(ConsoleType::Warn);
+
+        assert_eq!(writer.write(b"12\xff3").unwrap(), 2);
+        assert_eq!(writer.write(b"\xff3").unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(writer.buffer(), b"12");
+        writer.clear();
+
+        //Incomplete sequence is kept until next write.
+        assert_eq!(writer.write(&"ロ".as_bytes()[..2]).unwrap(), 2);
+        assert!(writer.is_empty());
+        assert_eq!(writer.write(&"ロ3".as_bytes()[2..]).unwrap(), 2);
+        assert_eq!(writer.buffer_str(), "ロ3");
+
+        //Sequence, that is broken by next write, is replaced.
+        assert_eq!(writer.write(b"\xe3").unwrap(), 1);
+        assert_eq!(writer.write(b"4").unwrap(), 1);
+        assert_eq!(writer.write(b"\xe3").unwrap(), 1);
+        assert_eq!(writer.write(b"\xff").unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(writer.buffer_str(), "ロ3\u{FFFD}4\u{FFFD}");
+        writer.clear();
+
+        writer.write_all(b"x\xe3").unwrap();
+        drop(writer);
+
+        assert_eq!(take_captured(), [("warn", "x\u{FFFD}".to_owned())]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_write_vectored_via_io() {
+        use std::io::{IoSlice, Write};
+
+        let mut writer = Console::new(ConsoleType::Warn);
+        let text = "1ロ2".as_bytes();
+
+        let bufs = [IoSlice::new(&text[..2]), IoSlice::new(&text[2..3]), IoSlice::new(&text[3..]), IoSlice::new(b"3\xff4")];
+        assert_eq!(writer.write_vectored(&bufs).unwrap(), text.len() + 1);
+        assert_eq!(writer.buffer_str(), "1ロ23");
+        writer.clear();
+
+        let bufs = [IoSlice::new(&text[..2]), IoSlice::new(b"\xff")];
+        assert_eq!(writer.write_vectored(&bufs).unwrap(), 2);
+        let bufs = [IoSlice::new(b"\xff"), IoSlice::new(b"1")];
+        assert_eq!(writer.write_vectored(&bufs).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(writer.buffer_str(), "1\u{FFFD}");
+        writer.clear();
     }
 }
@@ -0,0 +1,320 @@
+#[cfg(feature = "module-prefix")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __module_prefix {
+    () => {
+        core::concat!("[", core::module_path!(), "] ")
+    }
+}
+
+#[cfg(not(feature = "module-prefix"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __module_prefix {
+    () => {
+        ""
+    }
+}
+
+#[cfg(feature = "explicit-newline")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __console_ln {
+    ($typ:expr, $($arg:tt)*) => {
+        $crate::console!($typ, "{}\n", format_args!($($arg)*))
+    }
+}
+
+#[cfg(not(feature = "explicit-newline"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __console_ln {
+    ($typ:expr, $($arg:tt)*) => {
+        $crate::console!($typ, $($arg)*)
+    }
+}
+
+#[macro_export]
+///Writes formatted message into console with specified `ConsoleType`.
+///
+///With `module-prefix` feature message is prefixed with `[<module path>] ` of the caller.
+///
+///If formatting panics, text written so far is flushed when `Console` is dropped during unwinding.
+///With `panic = "abort"` there is no unwinding, so partial message is lost.
+///
+///```rust,no_run
+///use web_log::ConsoleType;
+///
+///web_log::console!(ConsoleType::Warn, "Warning {}", 1);
+///```
+macro_rules! console {
+    ($typ:expr, $($arg:tt)*) => {{
+        let typ: $crate::ConsoleType = $typ;
+        if typ.is_enabled() {
+            $crate::__write_console(typ, $crate::__module_prefix!(), format_args!($($arg)*));
+        }
+    }}
+}
+
+#[macro_export]
+///`print` alternative to write message with INFO priority.
+///
+///Each invocation creates and drops its own `Console`, therefore consecutive `print` calls
+///produce separate console entries.
+///Reuse persistent `Console` to build single entry piecemeal.
+macro_rules! print {
+    ($($arg:tt)*) => {{
+        $crate::console!($crate::ConsoleType::Info, $($arg)*);
+    }}
+}
+
+#[macro_export]
+///`eprint` alternative to write message with ERROR priority.
+///
+///Each invocation creates and drops its own `Console`, therefore consecutive `eprint` calls
+///produce separate console entries.
+///Reuse persistent `Console` to build single entry piecemeal.
+macro_rules! eprint {
+    ($($arg:tt)*) => {{
+        $crate::console!($crate::ConsoleType::Error, $($arg)*);
+    }}
+}
+
+#[macro_export]
+///`println` alternative to write message with INFO priority.
+///
+///Console already puts each message on new line, so no newline is written by default.
+///With `explicit-newline` feature this and other `*ln` macros append `\n`,
+///so that messages are separated when written into custom `Sink`.
+///Browser's console shows it as empty line at the end of message.
+macro_rules! println {
+    () => {{
+        $crate::println!(" ");
+    }};
+    ($($arg:tt)*) => {{
+        $crate::__console_ln!($crate::ConsoleType::Info, $($arg)*);
+    }}
+}
+
+#[macro_export]
+///`eprintln` alternative to write message with ERROR priority.
+macro_rules! eprintln {
+    () => {{
+        $crate::eprintln!(" ");
+    }};
+    ($($arg:tt)*) => {{
+        $crate::__console_ln!($crate::ConsoleType::Error, $($arg)*);
+    }}
+}
+
+#[macro_export]
+///`println` alternative to write message with WARN priority.
+macro_rules! warnln {
+    () => {{
+        $crate::warnln!(" ");
+    }};
+    ($($arg:tt)*) => {{
+        $crate::__console_ln!($crate::ConsoleType::Warn, $($arg)*);
+    }}
+}
+
+#[macro_export]
+///`println` alternative to write message with INFO priority.
+macro_rules! infoln {
+    () => {{
+        $crate::infoln!(" ");
+    }};
+    ($($arg:tt)*) => {{
+        $crate::__console_ln!($crate::ConsoleType::Info, $($arg)*);
+    }}
+}
+
+#[macro_export]
+///`println` alternative to write message with DEBUG priority.
+macro_rules! debugln {
+    () => {{
+        $crate::debugln!(" ");
+    }};
+    ($($arg:tt)*) => {{
+        $crate::__console_ln!($crate::ConsoleType::Debug, $($arg)*);
+    }}
+}
+
+#[macro_export]
+///`println` alternative to write message with stack trace via `console.trace`.
+macro_rules! traceln {
+    () => {{
+        $crate::traceln!(" ");
+    }};
+    ($($arg:tt)*) => {{
+        $crate::__console_ln!($crate::ConsoleType::Trace, $($arg)*);
+    }}
+}
+
+#[macro_export]
+///`dbg` alternative to write expression with its value with DEBUG priority.
+///
+///Evaluates to the value of expression, same as `std::dbg`.
+macro_rules! dbg {
+    () => {{
+        $crate::console!($crate::ConsoleType::Debug, "[{}:{}:{}]", core::file!(), core::line!(), core::column!());
+    }};
+    ($val:expr $(,)?) => {
+        match $val {
+            tmp => {
+                $crate::console!($crate::ConsoleType::Debug, "[{}:{}:{}] {} = {:#?}", core::file!(), core::line!(), core::column!(), core::stringify!($val), &tmp);
+                tmp
+            }
+        }
+    };
+    ($($val:expr),+ $(,)?) => {
+        ($($crate::dbg!($val)),+,)
+    };
+}
+
+#[macro_export]
+///Writes message via `console.assert` if condition is false.
+///
+///Mirrors `assert` syntax, but doesn't panic.
+///Condition is evaluated exactly once and message is formatted only on failure.
+macro_rules! assert {
+    ($cond:expr $(,)?) => {{
+        if !$cond {
+            $crate::__assert_args($crate::__module_prefix!(), format_args!("{}", core::concat!("assertion failed: ", core::stringify!($cond))));
+        }
+    }};
+    ($cond:expr, $($arg:tt)+) => {{
+        if !$cond {
+            $crate::__assert_args($crate::__module_prefix!(), format_args!($($arg)+));
+        }
+    }};
+}
+
+#[macro_export]
+///Writes `left`/`right` comparison with ERROR priority if values are not equal.
+///
+///Mirrors `assert_eq` syntax, including optional custom message, but panics only with `assert-panic` feature.
+///Operands are evaluated exactly once and formatted via `Debug` only on failure.
+macro_rules! assert_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => if !(*left == *right) {
+                $crate::__assert_failed($crate::__module_prefix!(), "==", &*left, &*right, None);
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => if !(*left == *right) {
+                $crate::__assert_failed($crate::__module_prefix!(), "==", &*left, &*right, Some(format_args!($($arg)+)));
+            }
+        }
+    };
+}
+
+#[macro_export]
+///Writes `left`/`right` comparison with ERROR priority if values are equal.
+///
+///Mirrors `assert_ne` syntax, including optional custom message, but panics only with `assert-panic` feature.
+///Operands are evaluated exactly once and formatted via `Debug` only on failure.
+macro_rules! assert_ne {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => if *left == *right {
+                $crate::__assert_failed($crate::__module_prefix!(), "!=", &*left, &*right, None);
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => if *left == *right {
+                $crate::__assert_failed($crate::__module_prefix!(), "!=", &*left, &*right, Some(format_args!($($arg)+)));
+            }
+        }
+    };
+}
+
+#[macro_export]
+///Writes message followed by ` key=value` pairs, formatting values via `Display`.
+///
+///Each value is evaluated exactly once, only if `ConsoleType` is enabled.
+///
+///```rust,no_run
+///use web_log::ConsoleType;
+///
+///let id = 1;
+///web_log::kv!(ConsoleType::Info, "request done", id = id, status = 200);
+///```
+macro_rules! kv {
+    ($typ:expr, $msg:expr $(, $key:ident = $val:expr)* $(,)?) => {{
+        $crate::console!($typ, core::concat!("{}", $(" ", core::stringify!($key), "={}"),*), $msg $(, $val)*);
+    }};
+}
+
+#[macro_export]
+///Writes message with WARN priority if condition is false, continuing execution.
+///
+///Condition is evaluated exactly once and message is formatted only on failure.
+///
+///```rust,no_run
+///let value = 1;
+///web_log::warn_if!(value > 0, "value {} is not positive", value);
+///```
+macro_rules! warn_if {
+    ($cond:expr, $($arg:tt)+) => {{
+        if !$cond {
+            $crate::console!($crate::ConsoleType::Warn, $($arg)+);
+        }
+    }};
+}
+
+#[macro_export]
+///Starts new group with formatted label, returning `Group` guard.
+///
+///Group ends when guard is dropped, so it must be bound to variable.
+///
+///```rust,no_run
+///let _group = web_log::group!("request {}", 1);
+///web_log::println!("inside group");
+///```
+macro_rules! group {
+    ($($arg:tt)+) => {
+        $crate::group_args(format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+///Writes each argument as separate argument of `console.log`.
+///
+///Each argument is converted into `JsValue` so devtools can display it as it is.
+///Up to 8 arguments are supported.
+///
+///```rust,no_run
+///web_log::log_args!("value:", 1, true);
+///```
+macro_rules! log_args {
+    ($a:expr $(,)?) => {{
+        $crate::log_values(&[$crate::JsValue::from($a)]);
+    }};
+    ($a:expr, $b:expr $(,)?) => {{
+        $crate::log_values(&[$crate::JsValue::from($a), $crate::JsValue::from($b)]);
+    }};
+    ($a:expr, $b:expr, $c:expr $(,)?) => {{
+        $crate::log_values(&[$crate::JsValue::from($a), $crate::JsValue::from($b), $crate::JsValue::from($c)]);
+    }};
+    ($a:expr, $b:expr, $c:expr, $d:expr $(,)?) => {{
+        $crate::log_values(&[$crate::JsValue::from($a), $crate::JsValue::from($b), $crate::JsValue::from($c), $crate::JsValue::from($d)]);
+    }};
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr $(,)?) => {{
+        $crate::log_values(&[$crate::JsValue::from($a), $crate::JsValue::from($b), $crate::JsValue::from($c), $crate::JsValue::from($d), $crate::JsValue::from($e)]);
+    }};
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $f:expr $(,)?) => {{
+        $crate::log_values(&[$crate::JsValue::from($a), $crate::JsValue::from($b), $crate::JsValue::from($c), $crate::JsValue::from($d), $crate::JsValue::from($e), $crate::JsValue::from($f)]);
+    }};
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $f:expr, $g:expr $(,)?) => {{
+        $crate::log_values(&[$crate::JsValue::from($a), $crate::JsValue::from($b), $crate::JsValue::from($c), $crate::JsValue::from($d), $crate::JsValue::from($e), $crate::JsValue::from($f), $crate::JsValue::from($g)]);
+    }};
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $f:expr, $g:expr, $h:expr $(,)?) => {{
+        $crate::log_values(&[$crate::JsValue::from($a), $crate::JsValue::from($b), $crate::JsValue::from($c), $crate::JsValue::from($d), $crate::JsValue::from($e), $crate::JsValue::from($f), $crate::JsValue::from($g), $crate::JsValue::from($h)]);
+    }};
+}
@@ -0,0 +1,56 @@
+//! Node.js backend
+//!
+//! Writes text into `process.stderr` for `Error`, `Warn` and `Trace`, and into `process.stdout` otherwise.
+//! Unlike console, process streams do not terminate output with newline, so it is written after each message.
+//!
+//! Only text output is affected, the rest (e.g. groups or styling) still uses `console`.
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["process", "stdout"], js_name = write)]
+    fn stdout_write(s: &str);
+    #[wasm_bindgen(js_namespace = ["process", "stderr"], js_name = write)]
+    fn stderr_write(s: &str);
+}
+
+#[inline]
+fn stdout_line(text: &str) {
+    stdout_write(text);
+    stdout_write("\n");
+}
+
+#[inline]
+fn stderr_line(text: &str) {
+    stderr_write(text);
+    stderr_write("\n");
+}
+
+pub fn error(text: &str) {
+    stderr_line(text);
+}
+
+pub fn warn(text: &str) {
+    stderr_line(text);
+}
+
+pub fn trace(text: &str) {
+    stderr_line(text);
+}
+
+pub fn info(text: &str) {
+    stdout_line(text);
+}
+
+pub fn debug(text: &str) {
+    stdout_line(text);
+}
+
+pub fn log(text: &str) {
+    stdout_line(text);
+}
+
+pub fn dir(text: &str) {
+    stdout_line(text);
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "disable")]
+
+use web_log::{Console, ConsoleType};
+
+//Real bindings cannot be called outside of wasm, so any call into JS would panic.
+#[test]
+fn should_discard_everything() {
+    assert!(!ConsoleType::Error.is_enabled());
+
+    let mut is_evaluated = false;
+    web_log::println!("{}", {
+        is_evaluated = true;
+        1
+    });
+    web_log::eprintln!("{}", {
+        is_evaluated = true;
+        2
+    });
+    assert!(!is_evaluated);
+    assert_eq!(web_log::dbg!(3), 3);
+
+    let mut writer = Console::new(ConsoleType::Error);
+    assert_eq!(writer.write_data(b"data"), 4);
+    assert!(writer.is_empty());
+    drop(writer);
+
+    let group = web_log::group("group");
+    web_log::count("count");
+    web_log::clear();
+    drop(group);
+    web_log::assert!(false);
+}
@@ -0,0 +1,35 @@
+#![cfg(all(feature = "eager", not(feature = "disable")))]
+
+use web_log::{ConsoleType, Sink, set_sink};
+
+use std::sync::Mutex;
+
+struct VecSink(Mutex<Vec<(ConsoleType, String)>>);
+
+impl Sink for VecSink {
+    fn write(&self, typ: ConsoleType, text: &str) {
+        self.0.lock().unwrap().push((typ, text.to_owned()));
+    }
+}
+
+static SINK: VecSink = VecSink(Mutex::new(Vec::new()));
+
+#[test]
+fn should_write_each_fragment_immediately() {
+    assert!(set_sink(&SINK).is_ok());
+
+    let (num, text) = (1, "Ф");
+    web_log::console!(ConsoleType::Warn, "{}-{}", num, text);
+
+    let captured = core::mem::take(&mut *SINK.0.lock().unwrap());
+    let prefix = if cfg!(feature = "module-prefix") {
+        "[eager] "
+    } else {
+        ""
+    };
+    assert_eq!(captured, [
+        (ConsoleType::Warn, format!("{}1", prefix)),
+        (ConsoleType::Warn, format!("{}-", prefix)),
+        (ConsoleType::Warn, format!("{}Ф", prefix)),
+    ]);
+}
@@ -0,0 +1,30 @@
+#![cfg(not(feature = "disable"))]
+
+use web_log::{ConsoleType, max_level, set_max_level};
+
+#[test]
+fn should_filter_by_max_level() {
+    assert_eq!(max_level(), ConsoleType::Trace);
+    assert!(ConsoleType::Trace.is_enabled());
+
+    set_max_level(ConsoleType::Warn);
+    assert_eq!(max_level(), ConsoleType::Warn);
+    assert!(ConsoleType::Error.is_enabled());
+    assert!(ConsoleType::Warn.is_enabled());
+    assert!(!ConsoleType::Info.is_enabled());
+    assert!(!ConsoleType::Log.is_enabled());
+    assert!(!ConsoleType::Dir.is_enabled());
+    assert!(!ConsoleType::Debug.is_enabled());
+    assert!(!ConsoleType::Trace.is_enabled());
+
+    //Disabled types are not formatted at all
+    let mut is_evaluated = false;
+    web_log::console!(ConsoleType::Info, "{}", {
+        is_evaluated = true;
+        1
+    });
+    assert!(!is_evaluated);
+
+    set_max_level(ConsoleType::Trace);
+    assert!(ConsoleType::Trace.is_enabled());
+}
@@ -0,0 +1,47 @@
+#![cfg(not(any(feature = "disable", feature = "eager")))]
+
+use web_log::{Console, ConsoleType, Sink, SetSinkError, set_sink};
+
+use std::sync::Mutex;
+
+struct VecSink(Mutex<Vec<(ConsoleType, String)>>);
+
+impl Sink for VecSink {
+    fn write(&self, typ: ConsoleType, text: &str) {
+        self.0.lock().unwrap().push((typ, text.to_owned()));
+    }
+}
+
+static SINK: VecSink = VecSink(Mutex::new(Vec::new()));
+
+#[test]
+fn should_write_into_global_sink() {
+    assert_ne!(web_log::backend(), "sink");
+    assert_eq!(set_sink(&SINK), Ok(()));
+    assert_eq!(web_log::backend(), "sink");
+    assert_eq!(set_sink(&SINK), Err(SetSinkError));
+
+    web_log::println!("Hello {}", 1);
+    web_log::eprintln!("Error");
+
+    let mut writer = Console::styled(ConsoleType::Warn, "color: red");
+    writer.write_str("styled");
+    drop(writer);
+
+    let captured = core::mem::take(&mut *SINK.0.lock().unwrap());
+    let prefix = if cfg!(feature = "module-prefix") {
+        "[sink] "
+    } else {
+        ""
+    };
+    let newline = if cfg!(feature = "explicit-newline") {
+        "\n"
+    } else {
+        ""
+    };
+    assert_eq!(captured, [
+        (ConsoleType::Info, format!("{}Hello 1{}", prefix, newline)),
+        (ConsoleType::Error, format!("{}Error{}", prefix, newline)),
+        (ConsoleType::Warn, "styled".to_owned()),
+    ]);
+}